@@ -9,6 +9,7 @@ use tokio_stream::StreamExt;
 use actix_web::{web, App, HttpResponse, HttpServer, Responder};
 use actix_cors::Cors;
 use serde_json::{json, to_string};
+use wasm_project3::{annotate, puzzle, variants};
 
 async fn create_database(client: &Client, db_name: &str) -> Result<(), Box<dyn Error>> {
    let _db = client.database(db_name);
@@ -348,6 +349,178 @@ async fn lose_toot(client: web::Data<Client>, name: web::Json<String>) -> impl R
 
 
 
+#[derive(serde::Deserialize)]
+struct gameData {
+   game: String,  // "connect4" or "toot-otto"
+   notation: String,
+}
+
+// annotate the game's moves with the engine, off the worker thread since the search takes a while
+async fn annotate_game(gameData: &gameData) -> Result<Vec<annotate::Annotation>, String> {
+   let notation = gameData.notation.clone();
+   let mode = variants::parse(&gameData.game)?;
+   web::block(move || annotate::annotate_notation(mode, &notation, &annotate::default_engine()))
+      .await
+      .map_err(|e| e.to_string())?
+}
+
+async fn annotate_only(gameData: web::Json<gameData>) -> impl Responder {
+   match annotate_game(&gameData).await {
+      Ok(annotations) => HttpResponse::Ok().json(annotations),
+      Err(e) => HttpResponse::BadRequest().body(e),
+   }
+}
+
+// store a finished game with the engine's annotation of every move, and send the annotation back
+async fn store_game(client: web::Data<Client>, gameData: web::Json<gameData>) -> impl Responder {
+   let annotations = match annotate_game(&gameData).await {
+      Ok(annotations) => annotations,
+      Err(e) => return HttpResponse::BadRequest().body(e),
+   };
+   let db = client.database("tempFromCompass");
+   let coll = db.collection::<Document>("games");
+   let doc = doc! {
+      "game": gameData.game.clone(),
+      "notation": gameData.notation.clone(),
+      "annotations": bson::to_bson(&annotations).unwrap(),
+   };
+
+   match coll.insert_one(doc, None).await {
+      Ok(_) => HttpResponse::Ok().json(annotations),
+      Err(e) => {
+         eprintln!("Error storing game: {}", e);
+         HttpResponse::InternalServerError().body("Error storing game")
+      }
+   }
+}
+
+
+
+
+
+
+
+
+
+
+
+
+
+
+
+
+#[derive(serde::Deserialize)]
+struct puzzleRequest {
+   game: String,  // "connect4" or "toot-otto"
+   self_play_games: usize,
+   seed: u64,
+}
+
+// find puzzles in the stored games and in new bot games, and store the ones not stored yet
+async fn generate_puzzles(client: web::Data<Client>, request: web::Json<puzzleRequest>) -> impl Responder {
+   if request.self_play_games > puzzle::MAX_SELF_PLAY_GAMES {
+      return HttpResponse::BadRequest().body(format!("at most {} self play games can be asked for, not {}", puzzle::MAX_SELF_PLAY_GAMES, request.self_play_games));
+   }
+   let db = client.database("tempFromCompass");
+   let games = db.collection::<Document>("games");
+   let mut notations = Vec::new();
+   let mut cursor = match games.find(doc! { "game": request.game.clone() }, None).await {
+      Ok(cursor) => cursor,
+      Err(e) => {
+         eprintln!("Error reading games: {}", e);
+         return HttpResponse::InternalServerError().body("Error reading games");
+      }
+   };
+   while let Some(Ok(document)) = cursor.next().await {
+      if let Some(notation) = document.get("notation").and_then(bson::Bson::as_str) {
+         notations.push(notation.to_string());
+      }
+   }
+
+   // the searches take a while, keep them off the worker thread
+   let mode = match variants::parse(&request.game) {
+      Ok(mode) => mode,
+      Err(e) => return HttpResponse::BadRequest().body(e),
+   };
+   let (self_play_games, seed) = (request.self_play_games, request.seed);
+   let found = web::block(move || -> Result<Vec<puzzle::Puzzle>, String> {
+      let mut found = puzzle::generate(mode, self_play_games, seed, puzzle::MAX_WIN_IN)?;
+      for notation in notations {
+         // a stored game that no longer loads is skipped, the others still give puzzles
+         found.extend(puzzle::puzzles_in_notation(mode, &notation, puzzle::MAX_WIN_IN).unwrap_or_default());
+      }
+      Ok(found)
+   }).await;
+   let found = match found {
+      Ok(Ok(found)) => found,
+      Ok(Err(e)) => return HttpResponse::BadRequest().body(e),
+      Err(e) => return HttpResponse::InternalServerError().body(e.to_string()),
+   };
+
+   let puzzles = db.collection::<Document>("puzzles");
+   let mut added = 0;
+   for found_puzzle in found {
+      let filter = doc! { "position": found_puzzle.position.clone() };
+      if let Ok(Some(_)) = puzzles.find_one(filter, None).await {
+         continue;
+      }
+      let doc = doc! {
+         "game": found_puzzle.game.clone(),
+         "position": found_puzzle.position.clone(),
+         "win_in": found_puzzle.win_in as i32,
+         "solution": bson::to_bson(&found_puzzle.solution).unwrap(),
+      };
+      if puzzles.insert_one(doc, None).await.is_ok() {
+         added += 1;
+      }
+   }
+
+   HttpResponse::Ok().json(added)
+}
+
+// the puzzle of the day for the game, the same for everyone until midnight UTC
+async fn daily_puzzle(client: web::Data<Client>, game: web::Path<String>) -> impl Responder {
+   let db = client.database("tempFromCompass");
+   let coll = db.collection::<Document>("puzzles");
+   let find_options = FindOptions::builder()
+       .sort(doc! { "position": 1 })
+       .build();
+   let mut cursor = match coll.find(doc! { "game": game.to_string() }, find_options).await {
+      Ok(cursor) => cursor,
+      Err(e) => {
+         eprintln!("Error reading puzzles: {}", e);
+         return HttpResponse::InternalServerError().body("Error reading puzzles");
+      }
+   };
+   let mut all_puzzles = Vec::new();
+   while let Some(Ok(document)) = cursor.next().await {
+      all_puzzles.push(document);
+   }
+   if all_puzzles.is_empty() {
+      return HttpResponse::NotFound().body("No puzzles stored yet");
+   }
+
+   let day = chrono::Utc::now().timestamp() / (24 * 60 * 60);
+   let mut document = all_puzzles.swap_remove(day as usize % all_puzzles.len());
+   document.remove("_id");
+   HttpResponse::Ok().json(document)
+}
+
+
+
+
+
+
+
+
+
+
+
+
+
+
+
+
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
    println!("running server...");
@@ -370,6 +543,10 @@ async fn main() -> std::io::Result<()> {
    .route("/losetoot", web::post().to(lose_toot))
    .route("/signin", web::post().to(sign_into_game))
    .route("/signup", web::post().to(create_account))
+   .route("/annotate", web::post().to(annotate_only))
+   .route("/storegame", web::post().to(store_game))
+   .route("/generatepuzzles", web::post().to(generate_puzzles))
+   .route("/dailypuzzle/{game}", web::get().to(daily_puzzle))
    })
    .bind("127.0.0.1:8080")?
    .run()
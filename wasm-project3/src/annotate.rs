@@ -0,0 +1,131 @@
+use serde::{Deserialize, Serialize};
+use tsify::Tsify;
+use wasm_bindgen::prelude::*;
+
+use crate::bot::{self, BotConfig, Position};
+use crate::evaluate::WIN_SCORE;
+use crate::js::{self, Piece};
+use crate::notation;
+use crate::rules::{Connect4, GameMode, GameState, Rules};
+use crate::Board;
+
+// Largest drop in evaluation that still counts as a good move, a little under a blocked threat
+const GOOD_DROP: i32 = 20;
+// Largest drop that is only an inaccuracy, about one threat handed over or missed
+const INACCURACY_DROP: i32 = 60;
+// Scores past this are won or lost positions found by the search
+const DECIDED: i32 = WIN_SCORE / 2;
+
+// How a move compares to the best one the engine found
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Tsify)]
+pub enum MoveQuality {
+    Best,
+    Good,
+    Inaccuracy,
+    Mistake,
+    // threw away a won position or walked into a lost one
+    Blunder,
+}
+
+impl MoveQuality {
+    // Classify a move by how much worse it scored than the best move, both scores from the
+    // view of the player who moved
+    pub fn classify(best: i32, played: i32) -> MoveQuality {
+        if played >= best {
+            MoveQuality::Best
+        } else if (best >= DECIDED && played >= DECIDED) || best <= -DECIDED {
+            // a slower win, or a position that was lost whatever was played
+            MoveQuality::Good
+        } else if best >= DECIDED || played <= -DECIDED {
+            MoveQuality::Blunder
+        } else if best - played <= GOOD_DROP {
+            MoveQuality::Good
+        } else if best - played <= INACCURACY_DROP {
+            MoveQuality::Inaccuracy
+        } else {
+            MoveQuality::Mistake
+        }
+    }
+}
+
+// A move of a game with the engine's verdict. Scores are from the view of the player who moved:
+// before is the score of the best move in the position, after is the score of the move played
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Tsify)]
+pub struct Annotation {
+    // number of the move in the game, counting from 1
+    pub ply: usize,
+    pub player_id: usize,
+    pub col: usize,
+    pub piece: Piece,
+    pub best_col: usize,
+    pub best_piece: Piece,
+    pub before: i32,
+    pub after: i32,
+    pub quality: MoveQuality,
+}
+
+// Annotate every move of a game played from the given empty board, searching each position
+// with the given bot settings. Fails like notation::replay if a move is not legal
+pub fn annotate<R>(rules: R, board: Board, moves: &[(usize, R::Piece)], config: &BotConfig) -> Result<Vec<Annotation>, String>
+where R: Rules + Clone, R::Piece: Into<Piece> {
+    notation::replay(rules.clone(), board.clone(), moves)?;
+    let mut state = GameState::new(rules, board);
+    let mut annotations = vec![];
+    for (i, &mv) in moves.iter().enumerate() {
+        let legal = state.legal_moves();
+        let scored = bot::score_moves(&mut state, &legal, config);
+        // the first of the top scored moves, in column order
+        let &((best_col, best_piece), before) = scored.iter().rev().max_by_key(|&&(_, score)| score).expect("move was legal");
+        let after = scored.iter().find(|&&(scored_mv, _)| scored_mv == mv).map_or(before, |&(_, score)| score);
+        annotations.push(Annotation{
+            ply: i + 1,
+            player_id: state.to_move,
+            col: mv.0,
+            piece: mv.1.into(),
+            best_col,
+            best_piece: best_piece.into(),
+            before,
+            after,
+            quality: MoveQuality::classify(before, after),
+        });
+        state.play(mv);
+    }
+    Ok(annotations)
+}
+
+// The bot settings games are annotated with, deep and without a node budget so every move is
+// judged to the full depth, the same on the server and in the browser
+pub fn default_engine() -> BotConfig {
+    BotConfig{node_budget: 0, ..BotConfig::hard()}
+}
+
+// Annotate a game written in notation, column-sequence notation for Connect4 and Toot-Otto
+// notation for Toot-Otto, on the standard board of the game
+pub fn annotate_notation(mode: GameMode, notation: &str, config: &BotConfig) -> Result<Vec<Annotation>, String> {
+    match mode {
+        GameMode::Connect4 => annotate(Connect4, Board::standard(mode), &notation::moves_C4(notation)?, config),
+        GameMode::TootOtto => {
+            let record = notation::parse_TO(notation)?;
+            let board = Board::standard(mode);
+            record.game(board.clone())?;
+            annotate(record.rules(), board, &record.moves, config)
+        },
+        _ => Err(notation::no_notation(mode)),
+    }
+}
+
+#[wasm_bindgen(unchecked_return_type = "Annotation[]")]
+// For Connect4 game
+// Annotate every move of a game in column-sequence notation, throws if a move is not legal
+#[allow(non_snake_case)]
+pub fn annotate_game_C4(notation: &str) -> Result<JsValue, JsValue> {
+    Ok(js::to_js(&annotate_notation(GameMode::Connect4, notation, &default_engine())?))
+}
+
+#[wasm_bindgen(unchecked_return_type = "Annotation[]")]
+// For Toot-Otto game
+// Annotate every move of a game in notation, throws if a move is not legal
+#[allow(non_snake_case)]
+pub fn annotate_game_TO(notation: &str) -> Result<JsValue, JsValue> {
+    Ok(js::to_js(&annotate_notation(GameMode::TootOtto, notation, &default_engine())?))
+}
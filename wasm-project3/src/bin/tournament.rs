@@ -0,0 +1,185 @@
+// Pit two bots against each other for a number of games and report how they did.
+//
+// Usage: tournament [--game VARIANT] [--games N] [--seed S]
+//                   [--rows R --cols C --connect N] [--wrap] <bot-a> <bot-b>
+//
+// The variant is an id from the variants registry: connect4 (the default), toot-otto, popout,
+// five-in-a-row or power-up.
+// The board defaults to the standard size of the game, --rows, --cols and --connect
+// play on a custom board instead (--connect does not matter for Toot-Otto). --wrap joins the
+// left and right edges of the board, so rows and diagonals wrap around.
+// Game i is played with seed S + i, so passing the seed printed by a run replays it.
+// A bot is a difficulty preset (easy, medium, hard, expert), optionally followed by
+// overrides of its settings, e.g. "hard:depth=6,temperature=0,node_budget=0" or
+// "hard:engine=mcts,iterations=5000" for the Monte Carlo engine.
+use std::env;
+use std::process;
+use std::time::Instant;
+
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use wasm_project3::bot::{self, BotConfig, Engine, Position, Status};
+use wasm_project3::rules::{Connect4, FiveInARow, GameMode, GameState, PopOut, PowerUp, TootOtto};
+use wasm_project3::{set_game, variants, Board};
+
+// Totals of a bot over the tournament
+#[derive(Default)]
+struct Record {
+    wins: u32,
+    draws: u32,
+    losses: u32,
+    moves: u32,
+    think_ms: f64,
+}
+
+// Parse a bot spec like "hard" or "hard:depth=6,blunder_chance=0"
+fn parse_bot(spec: &str) -> Result<BotConfig, String> {
+    let (name, overrides) = match spec.split_once(':') {
+        Some((name, overrides)) => (name, overrides),
+        None => (spec, ""),
+    };
+    let mut config = BotConfig::preset(name).ok_or(format!("unknown bot preset '{}'", name))?;
+    for setting in overrides.split(',').filter(|setting| !setting.is_empty()) {
+        let (key, value) = setting.split_once('=').ok_or(format!("expected key=value, got '{}'", setting))?;
+        let bad_value = || format!("invalid value '{}' for {}", value, key);
+        match key {
+            "depth" => config.depth = value.parse().map_err(|_| bad_value())?,
+            "temperature" => config.temperature = value.parse().map_err(|_| bad_value())?,
+            "blunder_chance" => config.blunder_chance = value.parse().map_err(|_| bad_value())?,
+            "block_threats" => config.block_threats = value.parse().map_err(|_| bad_value())?,
+            "time_budget_ms" => config.time_budget_ms = value.parse().map_err(|_| bad_value())?,
+            "node_budget" => config.node_budget = value.parse().map_err(|_| bad_value())?,
+            "engine" => config.engine = match value {
+                "minimax" => Engine::Minimax,
+                "mcts" => Engine::MonteCarlo,
+                _ => return Err(bad_value()),
+            },
+            "iterations" => config.iterations = value.parse().map_err(|_| bad_value())?,
+            "heuristic_playouts" => config.heuristic_playouts = value.parse().map_err(|_| bad_value())?,
+            _ => return Err(format!("unknown bot setting '{}'", key)),
+        }
+    }
+    Ok(config)
+}
+
+// Games longer than this many moves are a draw, Pop Out games can go on forever
+const MAX_GAME_MOVES: u32 = 200;
+
+// Outcome of one game, indexed by seat (0 moved first)
+struct GameResult {
+    winner: Option<usize>,
+    moves: [u32; 2],
+    think_ms: [f64; 2],
+}
+
+// Play one game between the two bots, `bots[0]` moving first
+fn play_game<P: Position>(mut pos: P, bots: [&BotConfig; 2], rng: &mut ChaCha8Rng) -> GameResult {
+    let mut result = GameResult{winner: None, moves: [0; 2], think_ms: [0.0; 2]};
+    let mut seat = 0;
+    loop {
+        let start = Instant::now();
+        let mv = match bot::choose_move(&mut pos, bots[seat], rng) {
+            Some(mv) => mv,
+            None => return result,
+        };
+        result.think_ms[seat] += start.elapsed().as_secs_f64() * 1000.0;
+        result.moves[seat] += 1;
+        match pos.play(mv) {
+            Status::Won => result.winner = Some(seat),
+            Status::Lost => result.winner = Some(1 - seat),
+            Status::Draw => {},
+            Status::Ongoing if result.moves[0] + result.moves[1] == MAX_GAME_MOVES => {},
+            Status::Ongoing => {
+                seat = 1 - seat;
+                continue;
+            }
+        }
+        return result;
+    }
+}
+
+fn usage() -> ! {
+    eprintln!("usage: tournament [--game connect4|toot-otto|popout|five-in-a-row|power-up] [--games N] [--seed S] [--rows R --cols C --connect N] [--wrap] <bot-a> <bot-b>");
+    eprintln!("bots are easy, medium, hard or expert, optionally with overrides like hard:depth=6,temperature=0");
+    process::exit(2);
+}
+
+fn main() {
+    let mut game = GameMode::Connect4;
+    let mut num_games = 10;
+    let mut seed: u64 = rand::thread_rng().gen::<u32>() as u64;
+    let mut specs = vec![];
+    let (mut num_rows, mut num_cols, mut win_len) = (None, None, None);
+    let mut wraps = false;
+
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--game" => game = args.next().and_then(|id| variants::find(&id.to_lowercase())).unwrap_or_else(|| usage()),
+            "--games" => num_games = args.next().and_then(|n| n.parse().ok()).unwrap_or_else(|| usage()),
+            "--seed" => seed = args.next().and_then(|n| n.parse().ok()).unwrap_or_else(|| usage()),
+            "--rows" => num_rows = Some(args.next().and_then(|n| n.parse().ok()).unwrap_or_else(|| usage())),
+            "--cols" => num_cols = Some(args.next().and_then(|n| n.parse().ok()).unwrap_or_else(|| usage())),
+            "--connect" => win_len = Some(args.next().and_then(|n| n.parse().ok()).unwrap_or_else(|| usage())),
+            "--wrap" => wraps = true,
+            "-h" | "--help" => usage(),
+            _ => specs.push(arg),
+        }
+    }
+    if specs.len() != 2 {
+        usage();
+    }
+    let bots: Vec<BotConfig> = specs.iter().map(|spec| parse_bot(spec).unwrap_or_else(|err| {
+        eprintln!("{}", err);
+        process::exit(2);
+    })).collect();
+
+    set_game(game);
+    let new_board = || {
+        let standard = Board::new();
+        let mut board = Board::with_size(
+            num_rows.unwrap_or(standard.num_rows()),
+            num_cols.unwrap_or(standard.num_cols()),
+            win_len.unwrap_or(standard.win_len()),
+        );
+        board.set_wraps(wraps);
+        board
+    };
+    let mut records = [Record::default(), Record::default()];
+    for game_num in 0..num_games {
+        // alternate which bot moves first, the bot in seat `seat` is bots[seat ^ first]
+        let first = game_num % 2;
+        let seats = [&bots[first], &bots[1 - first]];
+        let mut rng = ChaCha8Rng::seed_from_u64(seed + game_num as u64);
+        let result = match game {
+            GameMode::Connect4 => play_game(GameState::new(Connect4, new_board()), seats, &mut rng),
+            GameMode::TootOtto => play_game(GameState::new(TootOtto::default(), new_board()), seats, &mut rng),
+            GameMode::PopOut => play_game(GameState::new(PopOut, new_board()), seats, &mut rng),
+            GameMode::FiveInARow => play_game(GameState::new(FiveInARow, new_board()), seats, &mut rng),
+            GameMode::PowerUp => play_game(GameState::new(PowerUp::default(), new_board()), seats, &mut rng),
+        };
+        for seat in 0..2 {
+            let record = &mut records[seat ^ first];
+            record.moves += result.moves[seat];
+            record.think_ms += result.think_ms[seat];
+            match result.winner {
+                Some(winner) if winner == seat => record.wins += 1,
+                Some(_) => record.losses += 1,
+                None => record.draws += 1,
+            }
+        }
+    }
+
+    let total_moves = records[0].moves + records[1].moves;
+    let board = new_board();
+    println!("{} ({}x{}{}, connect {}): {} vs {}, {} games, seed {}",
+        game.name(), board.num_rows(), board.num_cols(), if board.wraps() { " wrapped" } else { "" },
+        board.win_len(), specs[0], specs[1], num_games, seed);
+    for (spec, record) in specs.iter().zip(records.iter()) {
+        println!("{:>20}  W {:<4} D {:<4} L {:<4} avg {:.1} ms/move",
+            spec, record.wins, record.draws, record.losses,
+            record.think_ms / record.moves.max(1) as f64);
+    }
+    println!("average game length: {:.1} moves", total_moves as f64 / num_games.max(1) as f64);
+}
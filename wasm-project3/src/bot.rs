@@ -0,0 +1,348 @@
+use wasm_bindgen::prelude::*;
+use rand::Rng;
+
+use crate::evaluate::WIN_SCORE;
+use crate::rules::{GameState, Rules};
+use crate::mcts;
+
+// Larger than any score a position can be evaluated to
+const INFINITY: i32 = i32::MAX / 2;
+
+// Positions the presets may search for a move, sized to take about as long in the browser
+// as the time budgets they used to have
+const EASY_NODES: u32 = 2_000;
+const MEDIUM_NODES: u32 = 5_000;
+const HARD_NODES: u32 = 10_000;
+const EXPERT_NODES: u32 = 15_000;
+
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq)]
+// How a bot looks for its moves
+pub enum Engine {
+    // alpha-beta search to a fixed depth
+    Minimax,
+    // Monte Carlo tree search, playing games out at random
+    MonteCarlo,
+}
+
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq)]
+// Settings that shape how a bot plays
+pub struct BotConfig {
+    // how many moves ahead the bot searches, 0 plays randomly
+    pub depth: u32,
+    // how loosely minimax picks moves by score, 0 always plays the best move
+    pub temperature: f64,
+    // chance of playing a random move instead of searching
+    pub blunder_chance: f64,
+    // whether the bot always takes an immediate win, blocks the opponent's and never hands one over
+    pub block_threats: bool,
+    // milliseconds the search may take before settling on the deepest finished depth, 0 for no limit
+    // Searches cut short by the clock depend on the machine, so only those finishing within
+    // the budget replay exactly from the same seed
+    pub time_budget_ms: f64,
+    // positions minimax may search before settling on the deepest finished depth, 0 for no limit
+    // It stops the search at the same point on every machine, so the presets use it instead of
+    // a time budget and replay exactly from the same seed
+    pub node_budget: u32,
+    // search engine used to pick moves
+    pub engine: Engine,
+    // Monte Carlo iterations per move, 0 runs for the whole time budget instead
+    pub iterations: u32,
+    // whether Monte Carlo playouts take wins and avoid losses instead of moving purely at random
+    pub heuristic_playouts: bool,
+}
+
+#[wasm_bindgen]
+impl BotConfig {
+    #[wasm_bindgen(constructor)]
+    pub fn new(depth: u32, temperature: f64, blunder_chance: f64, block_threats: bool, time_budget_ms: f64) -> BotConfig {
+        BotConfig{
+            depth, temperature, blunder_chance, block_threats, time_budget_ms, node_budget: 0,
+            engine: Engine::Minimax, iterations: 0, heuristic_playouts: true,
+        }
+    }
+
+    // Monte Carlo tree search bot running the given iterations, or for the time budget if 0
+    pub fn monte_carlo(iterations: u32, time_budget_ms: f64) -> BotConfig {
+        BotConfig{engine: Engine::MonteCarlo, iterations, ..BotConfig::new(0, 0.0, 0.0, true, time_budget_ms)}
+    }
+
+    // shallow and loose, misses most threats
+    pub fn easy() -> BotConfig {
+        BotConfig{node_budget: EASY_NODES, ..BotConfig::new(1, 40.0, 0.3, false, 0.0)}
+    }
+
+    // looks a few moves ahead, never misses an immediate win or block
+    pub fn medium() -> BotConfig {
+        BotConfig{node_budget: MEDIUM_NODES, ..BotConfig::new(3, 8.0, 0.1, true, 0.0)}
+    }
+
+    // deeper search with the occasional slip
+    pub fn hard() -> BotConfig {
+        BotConfig{node_budget: HARD_NODES, ..BotConfig::new(5, 2.0, 0.02, true, 0.0)}
+    }
+
+    // deepest search, always plays its best move
+    pub fn expert() -> BotConfig {
+        BotConfig{node_budget: EXPERT_NODES, ..BotConfig::new(8, 0.0, 0.0, true, 0.0)}
+    }
+
+    // return the preset for the given difficulty name ("easy", "medium", "hard" or "expert")
+    pub fn preset(name: &str) -> Option<BotConfig> {
+        match name {
+            "easy" => Some(BotConfig::easy()),
+            "medium" => Some(BotConfig::medium()),
+            "hard" => Some(BotConfig::hard()),
+            "expert" => Some(BotConfig::expert()),
+            _ => None,
+        }
+    }
+}
+
+// Result of a move for the player who made it
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Status {
+    Ongoing,
+    Won,
+    Lost,
+    Draw,
+}
+
+// A game position the bots can search
+pub trait Position {
+    type Move: Copy + PartialEq;
+    // every move the side to move may play
+    fn legal_moves(&self) -> Vec<Self::Move>;
+    // play a move for the side to move and hand the turn over
+    fn play(&mut self, mv: Self::Move) -> Status;
+    // take back a move made with play
+    fn undo(&mut self, mv: Self::Move);
+    // score the position from the view of the side to move
+    fn evaluate(&self) -> i32;
+}
+
+// Any game played by a set of Rules can be searched, a move is a column and the piece dropped in it
+impl<R: Rules> Position for GameState<R> {
+    type Move = (usize, R::Piece);
+
+    fn legal_moves(&self) -> Vec<(usize, R::Piece)> {
+        self.rules.legal_moves(&self.board, self.to_move)
+    }
+
+    fn play(&mut self, (col, piece): (usize, R::Piece)) -> Status {
+        let player_id = self.to_move;
+        let row = self.rules.drop_piece(&mut self.board, col, player_id, piece).expect("column is full");
+        self.to_move = 3 - player_id;
+        match self.rules.outcome(&self.board, row, col, player_id) {
+            None => Status::Ongoing,
+            Some(outcome) => match self.rules.winner(outcome) {
+                Some(winner) if winner == player_id => Status::Won,
+                Some(_) => Status::Lost,
+                None => Status::Draw,
+            },
+        }
+    }
+
+    fn undo(&mut self, (col, piece): (usize, R::Piece)) {
+        self.to_move = 3 - self.to_move;
+        self.rules.undo_piece(&mut self.board, col, self.to_move, piece);
+    }
+
+    fn evaluate(&self) -> i32 {
+        self.rules.evaluate(&self.board, self.to_move)
+    }
+}
+
+// Current time in milliseconds, used to keep the search within its time budget
+fn now_ms() -> f64 {
+    #[cfg(target_arch = "wasm32")]
+    { js_sys::Date::now() }
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0.0, |elapsed| elapsed.as_secs_f64() * 1000.0)
+    }
+}
+
+// Point in time or number of positions searched the search has to stop at
+pub(crate) struct Deadline {
+    end_ms: Option<f64>,
+    max_nodes: Option<u32>,
+    nodes: u32,
+}
+
+impl Deadline {
+    pub(crate) fn new(budget_ms: f64, node_budget: u32) -> Deadline {
+        let end_ms = if budget_ms > 0.0 { Some(now_ms() + budget_ms) } else { None };
+        let max_nodes = if node_budget > 0 { Some(node_budget) } else { None };
+        Deadline{end_ms, max_nodes, nodes: 0}
+    }
+
+    // count a position searched, only looking at the clock every so often as it is slow to
+    // read in the browser
+    fn expired(&mut self) -> bool {
+        self.nodes += 1;
+        if self.max_nodes.is_some_and(|max_nodes| self.nodes > max_nodes) {
+            return true;
+        }
+        match self.end_ms {
+            Some(end_ms) if self.nodes.is_multiple_of(512) => now_ms() > end_ms,
+            _ => false,
+        }
+    }
+
+    // look at the clock right away, for callers doing plenty of work between checks
+    pub(crate) fn expired_now(&self) -> bool {
+        self.end_ms.is_some_and(|end_ms| now_ms() > end_ms)
+    }
+}
+
+// Score a finished or searched move for the player who made it. Wins found with more
+// depth left are worth more so the bot goes for the quickest one.
+fn status_score(status: Status, depth: u32) -> Option<i32> {
+    match status {
+        Status::Won => Some(WIN_SCORE + depth as i32),
+        Status::Lost => Some(-WIN_SCORE - depth as i32),
+        Status::Draw => Some(0),
+        Status::Ongoing => None,
+    }
+}
+
+// Negamax search with alpha-beta pruning, returns None if the deadline passed
+fn negamax<P: Position>(pos: &mut P, depth: u32, mut alpha: i32, beta: i32, deadline: &mut Deadline) -> Option<i32> {
+    if deadline.expired() {
+        return None;
+    }
+    let mut best = -INFINITY;
+    for mv in pos.legal_moves() {
+        let status = pos.play(mv);
+        let score = match status_score(status, depth) {
+            Some(score) => Some(score),
+            None if depth <= 1 => Some(-pos.evaluate()),
+            None => negamax(pos, depth - 1, -beta, -alpha, deadline).map(|score| -score),
+        };
+        pos.undo(mv);
+        let score = score?;
+        best = best.max(score);
+        alpha = alpha.max(score);
+        if alpha >= beta {
+            break;
+        }
+    }
+    Some(if best == -INFINITY { 0 } else { best })
+}
+
+// Score the given moves with iterative deepening up to `config.depth`, keeping the
+// scores of the deepest search that finished within the time and node budgets
+pub fn score_moves<P: Position>(pos: &mut P, moves: &[P::Move], config: &BotConfig) -> Vec<(P::Move, i32)> {
+    let mut deadline = Deadline::new(config.time_budget_ms, config.node_budget);
+    let mut scored: Vec<(P::Move, i32)> = moves.iter().map(|&mv| (mv, 0)).collect();
+    for depth in 1..=config.depth.max(1) {
+        let mut deeper = vec![];
+        for &(mv, _) in scored.iter() {
+            let status = pos.play(mv);
+            let score = match status_score(status, depth) {
+                Some(score) => Some(score),
+                None if depth == 1 => Some(-pos.evaluate()),
+                None => negamax(pos, depth - 1, -INFINITY, INFINITY, &mut deadline).map(|score| -score),
+            };
+            pos.undo(mv);
+            match score {
+                Some(score) => deeper.push((mv, score)),
+                None => break,
+            }
+        }
+        if deeper.len() < scored.len() {
+            break;  // ran out of budget, keep the last finished depth
+        }
+        scored = deeper;
+    }
+    scored
+}
+
+// Score of the best move for the side to move searched as in score_moves, None if they have no moves
+pub fn best_score<P: Position>(pos: &mut P, config: &BotConfig) -> Option<i32> {
+    let moves = pos.legal_moves();
+    score_moves(pos, &moves, config).into_iter().map(|(_, score)| score).max()
+}
+
+// Pick one of the scored moves, the best one at temperature 0 and more freely as it rises
+fn pick_move<M: Copy, R: Rng>(scored: &[(M, i32)], temperature: f64, rng: &mut R) -> M {
+    let best = scored.iter().map(|&(_, score)| score).max().unwrap_or(0);
+    if temperature <= 0.0 {
+        let top: Vec<M> = scored.iter().filter(|&&(_, score)| score == best).map(|&(mv, _)| mv).collect();
+        return top[rng.gen_range(0..top.len())];
+    }
+    let weights: Vec<f64> = scored.iter()
+        .map(|&(_, score)| ((score - best) as f64 / temperature).exp())
+        .collect();
+    let mut choice = rng.gen::<f64>() * weights.iter().sum::<f64>();
+    for (&(mv, _), weight) in scored.iter().zip(weights) {
+        if choice < weight {
+            return mv;
+        }
+        choice -= weight;
+    }
+    scored[scored.len() - 1].0
+}
+
+// Return true if the side to move can win with their next move
+fn has_winning_move<P: Position>(pos: &mut P) -> bool {
+    pos.legal_moves().into_iter().any(|mv| {
+        let status = pos.play(mv);
+        pos.undo(mv);
+        status == Status::Won
+    })
+}
+
+// Narrow the legal moves down with simple rules, the way a sensible player would without
+// looking further ahead: take an immediate win if there is one, otherwise only keep moves
+// after which the opponent cannot win straight away. That blocks the opponent's threats and
+// rules out moves that hand them a win, like filling the space under their winning space.
+// If every move loses, all legal moves are returned.
+pub fn tactical_moves<P: Position>(pos: &mut P) -> Vec<P::Move> {
+    let moves = pos.legal_moves();
+    let mut safe = vec![];
+    for &mv in moves.iter() {
+        let status = pos.play(mv);
+        let loses = match status {
+            Status::Won => {
+                pos.undo(mv);
+                return vec![mv];
+            }
+            Status::Lost => true,
+            Status::Draw => false,
+            Status::Ongoing => has_winning_move(pos),
+        };
+        pos.undo(mv);
+        if !loses {
+            safe.push(mv);
+        }
+    }
+    if safe.is_empty() { moves } else { safe }
+}
+
+// Choose a move for the side to move according to the bot's config, None if there are no moves
+pub fn choose_move<P: Position, R: Rng>(pos: &mut P, config: &BotConfig, rng: &mut R) -> Option<P::Move> {
+    let moves = if config.block_threats { tactical_moves(pos) } else { pos.legal_moves() };
+    if moves.len() <= 1 {
+        return moves.first().copied();
+    }
+    let searches = config.depth > 0 || config.engine == Engine::MonteCarlo;
+    if !searches || rng.gen_bool(config.blunder_chance.clamp(0.0, 1.0)) {
+        return Some(moves[rng.gen_range(0..moves.len())]);
+    }
+    match config.engine {
+        Engine::Minimax => {
+            let scored = score_moves(pos, &moves, config);
+            Some(pick_move(&scored, config.temperature, rng))
+        }
+        Engine::MonteCarlo => {
+            // the most visited move is the one the search trusts most
+            let visited = mcts::search(pos, &moves, config, rng);
+            visited.iter().max_by_key(|&&(_, visits)| visits).map(|&(mv, _)| mv)
+        }
+    }
+}
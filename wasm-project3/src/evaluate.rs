@@ -0,0 +1,244 @@
+use crate::Board;
+
+// Score for a complete window (a spelled word or a full line of one player's pieces)
+pub const WIN_SCORE: i32 = 100_000;
+
+// Directions (row step, col step) of the 4-letter windows, rows count down from the top
+const DIRECTIONS: [(isize, isize); 4] = [
+    (0, 1),   // horizontal -
+    (1, 0),   // vertical |
+    (-1, 1),  // right diagonal /
+    (1, 1),   // left diagonal \
+];
+
+// Return the opposing word of a Toot-Otto word ("TOOT" <-> "OTTO")
+#[allow(non_snake_case)]
+pub fn opponent_word_TO(word: &str) -> &'static str {
+    if word == "TOOT" { "OTTO" } else { "TOOT" }
+}
+
+// Return the window of `len` spaces starting at the given space in the given direction,
+// or None if it runs off the board. On a board that wraps, a window may run over the side
+// edge but not so far along a row that it comes back to its own spaces. A window as long as a
+// wrapping row is the whole row wherever it starts, so only the one from column 1 is given.
+fn window_from(board: &Board, len: usize, row: isize, col: isize, (d_row, d_col): (isize, isize)) -> Option<Vec<(usize, usize)>> {
+    if d_row == 0 && len > board.num_cols {
+        return None;
+    }
+    if d_row == 0 && board.wraps && len == board.num_cols && board.space_at(row, col)?.1 != 1 {
+        return None;
+    }
+    (0..len as isize).map(|i| board.space_at(row + d_row * i, col + d_col * i)).collect()
+}
+
+// Return every window of `len` consecutive spaces on the board as (row, col) pairs
+pub fn windows(board: &Board, len: usize) -> Vec<Vec<(usize, usize)>> {
+    let mut all = vec![];
+    for &direction in DIRECTIONS.iter() {
+        for row in 1..=board.num_rows as isize {
+            for col in 1..=board.num_cols as isize {
+                all.extend(window_from(board, len, row, col, direction));
+            }
+        }
+    }
+    all
+}
+
+// Return the windows of `len` consecutive spaces that contain the given space
+pub fn windows_through(board: &Board, len: usize, row: usize, col: usize) -> Vec<Vec<(usize, usize)>> {
+    let mut all = vec![];
+    for &(d_row, d_col) in DIRECTIONS.iter() {
+        for i in 0..len as isize {
+            let start_row = row as isize - d_row * i;
+            let start_col = col as isize - d_col * i;
+            all.extend(window_from(board, len, start_row, start_col, (d_row, d_col)));
+        }
+    }
+    all
+}
+
+// Score a window from the view of `word`: None if the window can no longer spell it,
+// otherwise the number of letters already in place and the empty spaces left to fill
+fn partial_word(board: &Board, window: &[(usize, usize)], word: &str) -> Option<(usize, Vec<(usize, usize)>)> {
+    let mut placed = 0;
+    let mut empty = vec![];
+    for (&(row, col), wanted) in window.iter().zip(word.chars()) {
+        match board.letter_at(row, col) {
+            Some(letter) if letter == wanted => placed += 1,
+            Some(_) => return None,
+            None => empty.push((row, col)),
+        }
+    }
+    Some((placed, empty))
+}
+
+// Score a window from the view of `player_id`: None if anyone else has a piece in it,
+// otherwise the number of the player's pieces and the empty spaces left to fill
+fn partial_line(board: &Board, window: &[(usize, usize)], player_id: usize) -> Option<(usize, Vec<(usize, usize)>)> {
+    let mut placed = 0;
+    let mut empty = vec![];
+    for &(row, col) in window {
+        match board.player_at(row, col) {
+            Some(id) if id == player_id => placed += 1,
+            Some(_) => return None,
+            None => empty.push((row, col)),
+        }
+    }
+    Some((placed, empty))
+}
+
+// Weight of a partial word or line, counting a nearly complete one whose missing piece
+// can be dropped in right away as a threat
+fn partial_score(board: &Board, placed: usize, empty: &[(usize, usize)]) -> i32 {
+    if empty.is_empty() {
+        WIN_SCORE
+    } else if empty.len() == 1 {
+        if board.is_playable(empty[0].0, empty[0].1) { 60 } else { 20 }
+    } else {
+        (placed * placed) as i32
+    }
+}
+
+// Evaluate a Toot-Otto board for the player spelling `word`. Every 4-letter window is
+// scanned for partial spellings of both words (e.g. "TOO-" or "OT-O" for TOOT), own
+// progress counts for the player and the opponent's progress counts against them, with
+// opponent threats weighted heavier so moves that set the opponent up are avoided.
+#[allow(non_snake_case)]
+pub fn evaluate_board_TO(board: &Board, word: &str) -> i32 {
+    let opp_word = opponent_word_TO(word);
+    let mut score = 0;
+    for window in windows(board, word.len()) {
+        if let Some((placed, empty)) = partial_word(board, &window, word) {
+            score += partial_score(board, placed, &empty);
+        }
+        if let Some((placed, empty)) = partial_word(board, &window, opp_word) {
+            let opp_score = partial_score(board, placed, &empty);
+            score -= if empty.len() == 1 { opp_score * 2 } else { opp_score };
+        }
+    }
+    score
+}
+
+// Return true if the board spells `word` anywhere
+#[allow(non_snake_case)]
+pub fn has_word_TO(board: &Board, word: &str) -> bool {
+    windows(board, word.len()).iter().any(|window| {
+        matches!(partial_word(board, window, word), Some((placed, _)) if placed == word.len())
+    })
+}
+
+// Evaluate a Connect4 board for `player_id` in the same way, scanning every window of
+// win length spaces that only one of the players can still complete
+#[allow(non_snake_case)]
+pub fn evaluate_board_C4(board: &Board, player_id: usize) -> i32 {
+    let opp_id = 3 - player_id;
+    let mut score = 0;
+    for window in windows(board, board.win_len) {
+        if let Some((placed, empty)) = partial_line(board, &window, player_id) {
+            score += partial_score(board, placed, &empty);
+        }
+        if let Some((placed, empty)) = partial_line(board, &window, opp_id) {
+            let opp_score = partial_score(board, placed, &empty);
+            score -= if empty.len() == 1 { opp_score * 2 } else { opp_score };
+        }
+    }
+    score
+}
+
+// Return true if `player_id` has the win length in a row through the given space
+#[allow(non_snake_case)]
+pub fn is_win_at_C4(board: &Board, row: usize, col: usize, player_id: usize) -> bool {
+    windows_through(board, board.win_len, row, col).iter().any(|window| {
+        matches!(partial_line(board, window, player_id), Some((placed, _)) if placed == window.len())
+    })
+}
+
+// A row of one player's pieces on a Connect4 board, as far as it runs in its direction
+#[derive(Debug, Clone, PartialEq)]
+pub struct LineC4 {
+    pub player_id: usize,
+    pub direction: (isize, isize),
+    pub spaces: Vec<(usize, usize)>,
+}
+
+// Return the name of a direction of DIRECTIONS ("horizontal", "vertical", "right diagonal" or "left diagonal")
+pub fn direction_name(direction: (isize, isize)) -> &'static str {
+    match direction {
+        (0, 1) => "horizontal",
+        (1, 0) => "vertical",
+        (-1, 1) => "right diagonal",
+        _ => "left diagonal",
+    }
+}
+
+// Return the lines of at least the win length through the given space, one per direction
+// the piece there won in, with every space of the line even if it is longer than needed
+#[allow(non_snake_case)]
+pub fn lines_through_C4(board: &Board, row: usize, col: usize) -> Vec<LineC4> {
+    let player_id = match board.player_at(row, col) {
+        Some(player_id) => player_id,
+        None => return vec![],
+    };
+    let owned = |r: isize, c: isize| {
+        board.space_at(r, c).filter(|&(r, c)| board.player_at(r, c) == Some(player_id))
+    };
+    let mut lines = vec![];
+    for &(d_row, d_col) in DIRECTIONS.iter() {
+        // a row of a board that wraps can be owned all the way round, so stop after one lap
+        let max_len = if d_row == 0 && board.wraps() { board.num_cols } else { usize::MAX };
+        // back up to the first piece of the run, then walk to its last one
+        let (mut r, mut c) = (row as isize, col as isize);
+        let mut backed = 1;
+        while backed < max_len && owned(r - d_row, c - d_col).is_some() {
+            r -= d_row;
+            c -= d_col;
+            backed += 1;
+        }
+        let mut spaces = vec![];
+        while spaces.len() < max_len {
+            match owned(r, c) {
+                Some(space) => spaces.push(space),
+                None => break,
+            }
+            r += d_row;
+            c += d_col;
+        }
+        if spaces.len() >= board.win_len {
+            lines.push(LineC4{player_id, direction: (d_row, d_col), spaces});
+        }
+    }
+    lines
+}
+
+// A word spelled on a Toot-Otto board with its spaces in reading order
+#[derive(Debug, Clone, PartialEq)]
+pub struct WordTO {
+    pub word: &'static str,
+    pub spaces: Vec<(usize, usize)>,
+}
+
+// Return the TOOT and OTTO spelled in the given windows
+fn words_in(board: &Board, windows: Vec<Vec<(usize, usize)>>) -> Vec<WordTO> {
+    let mut words = vec![];
+    for window in windows {
+        for word in ["TOOT", "OTTO"] {
+            if matches!(partial_word(board, &window, word), Some((placed, _)) if placed == word.len()) {
+                words.push(WordTO{word, spaces: window.clone()});
+            }
+        }
+    }
+    words
+}
+
+// Return every TOOT and OTTO spelled anywhere on the board
+#[allow(non_snake_case)]
+pub fn words_TO(board: &Board) -> Vec<WordTO> {
+    words_in(board, windows(board, 4))
+}
+
+// Return every TOOT and OTTO spelled through the given space, which are all the words
+// created by a move that landed there
+#[allow(non_snake_case)]
+pub fn words_through_TO(board: &Board, row: usize, col: usize) -> Vec<WordTO> {
+    words_in(board, windows_through(board, 4, row, col))
+}
@@ -0,0 +1,161 @@
+use serde::{Deserialize, Serialize};
+
+use crate::notation::{self, RecordResult};
+use crate::rules::{GameState, Rules};
+
+// A move that was played, with who played it and whether it finished the game
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Played<M> {
+    pub mv: M,
+    pub by_bot: bool,
+    pub finished: bool,
+}
+
+// The moves of a game in the order they were played, with the ones taken back kept for redo
+#[derive(Debug, Clone)]
+pub struct History<M> {
+    played: Vec<Played<M>>,
+    undone: Vec<Played<M>>,
+}
+
+impl<M: Clone> History<M> {
+    pub fn new() -> History<M> {
+        History{played: vec![], undone: vec![]}
+    }
+
+    // record a new move, the moves that were taken back can no longer be redone
+    pub fn record(&mut self, mv: M, by_bot: bool, finished: bool) {
+        self.undone.clear();
+        self.played.push(Played{mv, by_bot, finished});
+    }
+
+    // take back the last move of a human and every bot move after it, so it is a human's turn again
+    // returns the moves taken back, last played first
+    pub fn undo(&mut self) -> Vec<M> {
+        let mut taken_back = vec![];
+        while let Some(played) = self.played.pop() {
+            let by_bot = played.by_bot;
+            taken_back.push(played.mv.clone());
+            self.undone.push(played);
+            if !by_bot {
+                break;
+            }
+        }
+        taken_back
+    }
+
+    // play again the last human move taken back and the bot moves that answered it
+    // returns the moves played again, first played first
+    pub fn redo(&mut self) -> Vec<M> {
+        let mut replayed = vec![];
+        while let Some(played) = self.undone.pop() {
+            replayed.push(played.mv.clone());
+            self.played.push(played);
+            // stop before the next human move
+            if self.undone.last().is_none_or(|next| !next.by_bot) {
+                break;
+            }
+        }
+        replayed
+    }
+
+    // the moves still on the board, first played first
+    pub fn moves(&self) -> Vec<M> {
+        self.played.iter().map(|played| played.mv.clone()).collect()
+    }
+
+    // true if the last move played finished the game
+    pub fn is_game_over(&self) -> bool {
+        self.played.last().is_some_and(|played| played.finished)
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.played.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.undone.is_empty()
+    }
+}
+
+impl<M: Clone> Default for History<M> {
+    fn default() -> History<M> {
+        History::new()
+    }
+}
+
+// A piece the given player dropped into a column, with the row it landed in
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Move<P> {
+    pub row: usize,
+    pub col: usize,
+    pub player_id: usize,
+    pub piece: P,
+}
+
+// A game being played, with the moves that led to its position so they can be taken back
+#[derive(Debug, Clone)]
+pub struct PlayedGame<R: Rules> {
+    pub state: GameState<R>,
+    history: History<Move<R::Piece>>,
+}
+
+impl<R: Rules> PlayedGame<R> {
+    // a game starting from the given position, none of whose moves can be taken back
+    pub fn new(state: GameState<R>) -> PlayedGame<R> {
+        PlayedGame{state, history: History::new()}
+    }
+
+    // Drop the given player's piece into the column and record it, the other player moves next.
+    // Returns the row it landed in, None if the player has no such piece left or it does not fit
+    pub fn play(&mut self, col: usize, player_id: usize, piece: R::Piece, by_bot: bool) -> Option<usize> {
+        let state = &mut self.state;
+        if !state.rules.pieces(&state.board, player_id).contains(&piece) {
+            return None;
+        }
+        let row = state.rules.drop_piece(&mut state.board, col, player_id, piece)?;
+        let finished = state.rules.outcome(&state.board, row, col, player_id).is_some();
+        self.history.record(Move{row, col, player_id, piece}, by_bot, finished);
+        state.to_move = 3 - player_id;
+        Some(row)
+    }
+
+    // take back the last human move and the bot moves that answered it, see History::undo
+    // returns the moves taken back, last played first
+    pub fn undo(&mut self) -> Vec<Move<R::Piece>> {
+        let taken_back = self.history.undo();
+        for mv in &taken_back {
+            self.state.rules.undo_piece(&mut self.state.board, mv.col, mv.player_id, mv.piece);
+        }
+        if let Some(first) = taken_back.last() {
+            self.state.to_move = first.player_id;
+        }
+        taken_back
+    }
+
+    // play again the moves taken back by the last undo, first played first
+    pub fn redo(&mut self) -> Vec<Move<R::Piece>> {
+        let replayed = self.history.redo();
+        for mv in &replayed {
+            self.state.rules.drop_piece(&mut self.state.board, mv.col, mv.player_id, mv.piece);
+        }
+        if let Some(last) = replayed.last() {
+            self.state.to_move = 3 - last.player_id;
+        }
+        replayed
+    }
+
+    pub fn history(&self) -> &History<Move<R::Piece>> {
+        &self.history
+    }
+
+    // the (col, piece) of every move on the board, first played first
+    pub fn moves(&self) -> Vec<(usize, R::Piece)> {
+        self.history.moves().iter().map(|mv| (mv.col, mv.piece)).collect()
+    }
+
+    // result of the game, Unfinished until the last move played ends it
+    pub fn result(&self) -> RecordResult {
+        notation::result_of(&self.state, self.moves().last())
+    }
+}
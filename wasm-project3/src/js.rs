@@ -0,0 +1,175 @@
+// Values the wasm functions hand to JS. They are serialized into plain objects with
+// serde-wasm-bindgen, so JS reads named fields like placed.row instead of placed[0], and tsify
+// declares their TypeScript types from the same serde attributes.
+
+use serde::{Deserialize, Serialize};
+use tsify::Tsify;
+use wasm_bindgen::prelude::*;
+
+use crate::evaluate::{self, LineC4, WordTO};
+use crate::rules::{GameMode, Power, TootOtto};
+use crate::{Board, Space};
+
+// serialize a value for JS, the types here only hold numbers and strings so this cannot fail
+pub(crate) fn to_js<T: Serialize>(value: &T) -> JsValue {
+    serde_wasm_bindgen::to_value(value).expect("value could not be passed to JS")
+}
+
+// A space on the board by its row and col
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Tsify)]
+pub struct Cell {
+    pub row: usize,
+    pub col: usize,
+}
+
+impl Cell {
+    pub fn all(spaces: &[(usize, usize)]) -> Vec<Cell> {
+        spaces.iter().map(|&(row, col)| Cell{row, col}).collect()
+    }
+}
+
+// A piece as JS draws it, the player id for Connect4 and the letter for Toot-Otto
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Tsify)]
+#[serde(untagged)]
+pub enum Piece {
+    Player(usize),
+    Letter(char),
+}
+
+impl From<usize> for Piece {
+    fn from(player_id: usize) -> Piece {
+        Piece::Player(player_id)
+    }
+}
+
+impl From<char> for Piece {
+    fn from(letter: char) -> Piece {
+        Piece::Letter(letter)
+    }
+}
+
+// A piece that was dropped or is on the board. Row is 0 when there was no space for it
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Tsify)]
+pub struct Placed {
+    pub row: usize,
+    pub col: usize,
+    pub piece: Piece,
+}
+
+impl Placed {
+    // the piece in the given space, None if it is empty
+    pub fn from_space(space: &Space) -> Option<Placed> {
+        let piece = match (&space.letter, &space.player) {
+            (Some(letter), _) => Piece::Letter(letter.chars().next()?),
+            (None, Some(player)) => Piece::Player(player.id),
+            (None, None) => return None,
+        };
+        Some(Placed{row: space.row, col: space.col, piece})
+    }
+
+    // every piece on the board, column by column from the top
+    pub fn all(board: &Board) -> Vec<Placed> {
+        board.pieces.iter().flatten().filter_map(Placed::from_space).collect()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Tsify)]
+pub struct BoardSize {
+    pub num_rows: usize,
+    pub num_cols: usize,
+    pub win_len: usize,
+    // whether rows and diagonals wrap around from the right edge to the left
+    pub wraps: bool,
+}
+
+// A word spelled on a Toot-Otto board with the id of the player who owns it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Tsify)]
+pub struct Word {
+    pub word: String,
+    pub player_id: usize,
+    pub spaces: Vec<Cell>,
+}
+
+impl Word {
+    pub fn new(rules: &TootOtto, word: &WordTO) -> Word {
+        Word{word: word.word.to_string(), player_id: rules.owner(word.word), spaces: Cell::all(&word.spaces)}
+    }
+}
+
+// A Connect4 line of one player's pieces, direction is "horizontal", "vertical",
+// "right diagonal" or "left diagonal"
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Tsify)]
+pub struct Line {
+    pub direction: String,
+    pub spaces: Vec<Cell>,
+}
+
+impl Line {
+    pub fn new(line: &LineC4) -> Line {
+        Line{direction: evaluate::direction_name(line.direction).to_string(), spaces: Cell::all(&line.spaces)}
+    }
+}
+
+// The T's and O's a Toot-Otto player has left
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Tsify)]
+pub struct LettersLeft {
+    pub t: usize,
+    pub o: usize,
+}
+
+// How good the board is for a player, win is the win length if they have already won
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Tsify)]
+pub struct Evaluation {
+    pub win: usize,
+    pub score: i32,
+}
+
+// A Pop Out move, a pop takes the piece from the bottom row and a drop lands in row
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Tsify)]
+pub struct MovePO {
+    pub row: usize,
+    pub col: usize,
+    pub pop: bool,
+}
+
+// A Power-Up move, row is where the piece ended up
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Tsify)]
+pub struct MovePU {
+    pub row: usize,
+    pub col: usize,
+    pub power: Power,
+}
+
+// A variant players can pick, see variants::VARIANTS
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Tsify)]
+pub struct Variant {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    pub num_rows: usize,
+    pub num_cols: usize,
+    pub win_len: usize,
+}
+
+impl Variant {
+    pub fn new(mode: GameMode) -> Variant {
+        let (num_rows, num_cols, win_len) = mode.board_size();
+        Variant{
+            id: mode.id().to_string(),
+            name: mode.name().to_string(),
+            description: mode.description().to_string(),
+            num_rows, num_cols, win_len,
+        }
+    }
+}
+
+// A ply of a Replay, evaluation is from player 1's view and positive when they are better
+#[derive(Debug, Clone, Serialize, Deserialize, Tsify)]
+pub struct ReplayPosition {
+    pub ply: usize,
+    pub to_move: usize,
+    pub board: Board,
+    #[tsify(optional)]
+    pub last_move: Option<Placed>,
+    pub evaluation: i32,
+}
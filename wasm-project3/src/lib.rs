@@ -3,14 +3,32 @@ extern crate lazy_static;
 extern crate mut_static;
 
 mod utils;
+pub mod evaluate;
+pub mod bot;
+pub mod mcts;
+pub mod rules;
+pub mod history;
+pub mod notation;
+pub mod js;
+pub mod replay;
+pub mod annotate;
+pub mod puzzle;
+pub mod variants;
 
 use wasm_bindgen::prelude::*;
 use mut_static::MutStatic;
+use std::any::Any;
 use std::ffi::CString;
 use std::os::raw::c_char;
-use js_sys::Array;
-use rand::prelude::*;
-use rand::Rng;
+use serde::{Deserialize, Serialize};
+use tsify::Tsify;
+use bot::BotConfig;
+use history::PlayedGame;
+use js::{to_js, BoardSize, Cell, Evaluation, LettersLeft, Line, MovePO, MovePU, Placed, Word};
+use notation::RecordResult;
+use rules::{Connect4, GameMode, GameState, OutcomeC4, OutcomeTO, PopOut, PopOutMove, Power, PowerUp, Rules, TootOtto};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
 
 // When the `wee_alloc` feature is enabled, use `wee_alloc` as the global
 // allocator.
@@ -18,15 +36,23 @@ use rand::Rng;
 #[global_allocator]
 static ALLOC: wee_alloc::WeeAlloc = wee_alloc::WeeAlloc::INIT;
 
-static C4_NUM_ROWS: usize = 6;
-static C4_NUM_COLS: usize = 7;
-static WIN_LEN_C4: usize = 4;
-static TO_NUM_ROWS: usize = 4;
-static TO_NUM_COLS: usize = 6;
+pub(crate) static C4_NUM_ROWS: usize = 6;
+pub(crate) static C4_NUM_COLS: usize = 7;
+pub(crate) static WIN_LEN_C4: usize = 4;
+pub(crate) static TO_NUM_ROWS: usize = 4;
+pub(crate) static TO_NUM_COLS: usize = 6;
+pub(crate) static WIN_LEN_TO: usize = 4;  // length of TOOT and OTTO
+pub(crate) static FIVE_NUM_ROWS: usize = 7;
+pub(crate) static FIVE_NUM_COLS: usize = 9;
+pub(crate) static WIN_LEN_FIVE: usize = 5;
 
 lazy_static! {
-    static ref BOARD: MutStatic<Board> = MutStatic::new();
-    static ref GAME: MutStatic<String> = MutStatic::new();
+    static ref PLAYING: MutStatic<Box<dyn AnyGame>> = MutStatic::new();
+    static ref GAME: MutStatic<GameMode> = MutStatic::new();
+    static ref SEED: MutStatic<u32> = MutStatic::new();
+    static ref NEXT_SEED: MutStatic<Option<u32>> = MutStatic::new();
+    static ref RNG: MutStatic<ChaCha8Rng> = MutStatic::new();
+    static ref TOOT_PLAYER: MutStatic<usize> = MutStatic::new();
 }
 
 #[wasm_bindgen]
@@ -34,17 +60,18 @@ extern {
     fn alert(s: &str);
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize, Tsify)]
 pub struct Player {
     id: usize,
 }
 
-#[wasm_bindgen]
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize, Tsify)]
 pub struct Space {
     row: usize,
     col: usize,
+    #[tsify(optional)]
     player: Option<Player>,
+    #[tsify(optional)]
     letter: Option<String>
 }
 
@@ -54,25 +81,106 @@ impl Space {
     }
 }
 
-#[derive(Debug)]
+// The game being played, whatever its rules, so the functions every mode shares need not
+// know them. The functions of one mode get at its rules through with_game
+trait AnyGame: Send + Sync {
+    fn board(&self) -> &Board;
+    fn to_move(&self) -> usize;
+    fn moves_played(&self) -> usize;
+    fn is_game_over(&self) -> bool;
+    fn can_undo(&self) -> bool;
+    fn can_redo(&self) -> bool;
+    // undo, returning the spaces of the moves taken back that filled one space, last played first
+    fn take_back(&mut self) -> Vec<Cell>;
+    // redo, returning the pieces of the moves played again that filled one space, first played first
+    fn play_again(&mut self) -> Vec<Placed>;
+    fn result(&self) -> RecordResult;
+    // the position to write as a string, None if the rules have no position strings
+    fn position(&self) -> Option<notation::Game>;
+    // the column of every move, None unless each one filled a single space
+    fn dropped_cols(&self) -> Option<Vec<usize>>;
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+}
+
+impl<R> AnyGame for PlayedGame<R>
+where
+    R: Rules + Clone + Send + Sync + 'static,
+    R::Piece: Send + Sync,
+{
+    fn board(&self) -> &Board {
+        &self.state.board
+    }
+    fn to_move(&self) -> usize {
+        self.state.to_move
+    }
+    fn moves_played(&self) -> usize {
+        self.history().moves().len()
+    }
+    fn is_game_over(&self) -> bool {
+        self.history().is_game_over()
+    }
+    fn can_undo(&self) -> bool {
+        self.history().can_undo()
+    }
+    fn can_redo(&self) -> bool {
+        self.history().can_redo()
+    }
+    fn take_back(&mut self) -> Vec<Cell> {
+        self.undo().iter()
+            .filter(|mv| self.state.rules.fills_one_space(mv.piece))
+            .map(|mv| Cell{row: mv.row, col: mv.col})
+            .collect()
+    }
+    fn play_again(&mut self) -> Vec<Placed> {
+        self.redo().iter()
+            .filter(|mv| self.state.rules.fills_one_space(mv.piece))
+            .filter_map(|mv| Placed::from_space(&self.state.board.get_piece(mv.row, mv.col)))
+            .collect()
+    }
+    fn result(&self) -> RecordResult {
+        PlayedGame::result(self)
+    }
+    fn position(&self) -> Option<notation::Game> {
+        notation::Game::of(&self.state)
+    }
+    fn dropped_cols(&self) -> Option<Vec<usize>> {
+        let moves = self.history().moves();
+        if !moves.iter().all(|mv| self.state.rules.fills_one_space(mv.piece)) {
+            return None;
+        }
+        Some(moves.iter().map(|mv| mv.col).collect())
+    }
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Tsify)]
 pub struct Board {
     pieces: Vec<Vec<Space>>, // Find R1C3, pieces[2][0]
     num_rows: usize,
-    num_cols: usize
+    num_cols: usize,
+    win_len: usize,  // pieces in a row needed to win
+    #[serde(default)]
+    wraps: bool,  // the left and right edges join, so rows and diagonals go round the board
 }
 
 impl Board {
+    // create a standard board for the current game mode
     pub fn new() -> Board {
-        let game = GAME.read().unwrap();
-        let num_rows; let num_cols;
-        if game.as_str() == "Connect4" {
-            num_rows = C4_NUM_ROWS;
-            num_cols = C4_NUM_COLS;
-        } else if game.as_str() == "Toot-Otto" {
-            num_rows = TO_NUM_ROWS;
-            num_cols = TO_NUM_COLS;
-        } else {
-            panic!("Game not defined before Board created!");
+        Board::standard(*GAME.read().expect("Game not defined before Board created!"))
+    }
+
+    // create an empty board of the standard size for the given game mode
+    pub fn standard(mode: GameMode) -> Board {
+        let (num_rows, num_cols, win_len) = mode.board_size();
+        Board::with_size(num_rows, num_cols, win_len)
+    }
+
+    // create an empty board of the given size where win_len pieces in a row win
+    pub fn with_size(num_rows: usize, num_cols: usize, win_len: usize) -> Board {
+        if let Err(msg) = Board::check_size(num_rows, num_cols, win_len) {
+            panic!("{}", msg);
         }
         // pieces = Vec of columns, where columns = Vec of rows
         let mut pieces = vec![];
@@ -83,13 +191,85 @@ impl Board {
             }
             pieces.push(col);
         }
-        Board{pieces: pieces, num_rows, num_cols}
+        Board{pieces, num_rows, num_cols, win_len, wraps: false}
+    }
+
+    // check a board of the given size can be created
+    pub fn check_size(num_rows: usize, num_cols: usize, win_len: usize) -> Result<(), String> {
+        if num_rows == 0 || num_cols == 0 || win_len < 2 {
+            return Err(format!(
+                "a {}x{} board with win length {} is not possible, boards need at least one row and column and a win length of 2 or more",
+                num_rows, num_cols, win_len,
+            ));
+        }
+        Ok(())
+    }
+
+    // create an empty board of the given size whose left and right edges join, as if it were
+    // wrapped around a cylinder
+    pub fn cylinder(num_rows: usize, num_cols: usize, win_len: usize) -> Board {
+        Board{wraps: true, ..Board::with_size(num_rows, num_cols, win_len)}
+    }
+
+    // create an empty board of the same size as this one, wrapping if it does
+    pub fn cleared(&self) -> Board {
+        Board{wraps: self.wraps, ..Board::with_size(self.num_rows, self.num_cols, self.win_len)}
+    }
+
+    pub fn num_rows(&self) -> usize {
+        self.num_rows
+    }
+    pub fn num_cols(&self) -> usize {
+        self.num_cols
+    }
+    pub fn win_len(&self) -> usize {
+        self.win_len
+    }
+    pub fn wraps(&self) -> bool {
+        self.wraps
+    }
+    pub fn set_wraps(&mut self, wraps: bool) {
+        self.wraps = wraps;
+    }
+    // return the (row, col) of the space at the given row and col, or None if it is off the
+    // board. On a board that wraps, columns past either edge come round from the other side.
+    pub fn space_at(&self, row: isize, col: isize) -> Option<(usize, usize)> {
+        let num_cols = self.num_cols as isize;
+        if row < 1 || row > self.num_rows as isize {
+            return None;
+        }
+        if self.wraps {
+            Some((row as usize, ((col - 1).rem_euclid(num_cols) + 1) as usize))
+        } else if col >= 1 && col <= num_cols {
+            Some((row as usize, col as usize))
+        } else {
+            None
+        }
     }
 
     // get the Space at given row and column
     pub fn get_piece(&self, row: usize, col: usize) -> Space {
         self.pieces[col - 1][row - 1].clone()
     }
+    // get the player id at the given row and column, if any (for Connect4)
+    pub fn player_at(&self, row: usize, col: usize) -> Option<usize> {
+        self.pieces[col - 1][row - 1].player.as_ref().map(|player| player.id)
+    }
+    // get the letter at the given row and column, if any (for Toot-Otto)
+    pub fn letter_at(&self, row: usize, col: usize) -> Option<char> {
+        self.pieces[col - 1][row - 1].letter.as_ref().and_then(|letter| letter.chars().next())
+    }
+    // return true if the given space is empty and a piece dropped in its column lands there
+    pub fn is_playable(&self, row: usize, col: usize) -> bool {
+        let space = &self.pieces[col - 1][row - 1];
+        if space.player.is_some() || space.letter.is_some() {
+            return false;
+        }
+        row == self.num_rows || {
+            let below = &self.pieces[col - 1][row];
+            below.player.is_some() || below.letter.is_some()
+        }
+    }
     // set the player at the given row and column (for Connect4)
     pub fn set_piece_player(&mut self, row: usize, col: usize, player: Player) {
         self.pieces[col - 1][row - 1].player = Some(player);
@@ -98,113 +278,156 @@ impl Board {
     pub fn set_piece_letter(&mut self, row: usize, col: usize, letter: String) {
         self.pieces[col - 1][row - 1].letter = Some(letter);
     }
-    // return vector of player ids in given row
-    pub fn get_ids_in_row(&self, row: usize) -> Vec<usize> {
-        let mut row_vec = vec![];
-        for col in 1..=self.num_cols {
-            match self.pieces[col - 1][row - 1].player.clone() {
-                None => row_vec.push(0),
-                Some(player) => row_vec.push(player.id),
-            }
+    // return true if the given column has no empty spaces left
+    pub fn is_col_full(&self, col: usize) -> bool {
+        let top = &self.pieces[col - 1][0];
+        top.player.is_some() || top.letter.is_some()
+    }
+    // return true if every column is full
+    pub fn is_full(&self) -> bool {
+        (1..=self.num_cols).all(|col| self.is_col_full(col))
+    }
+    // return the number of pieces the given player has on the board
+    pub fn count_pieces(&self, player_id: usize) -> usize {
+        self.pieces.iter().flatten()
+            .filter(|space| space.player.as_ref().is_some_and(|player| player.id == player_id))
+            .count()
+    }
+    // return the id of the player to move, player 1 moves first and the players alternate
+    pub fn player_to_move(&self) -> usize {
+        if self.count_pieces(1) > self.count_pieces(2) { 2 } else { 1 }
+    }
+    // return the row a piece dropped in the given column lands in, or None if the column is full
+    pub fn drop_row(&self, col: usize) -> Option<usize> {
+        (1..=self.num_rows).rev().find(|&row| {
+            let space = &self.pieces[col - 1][row - 1];
+            space.player.is_none() && space.letter.is_none()
+        })
+    }
+    // drop a player's piece into the given column, returns the row or None if the column is full
+    pub fn drop_player(&mut self, col: usize, player: Player) -> Option<usize> {
+        let row = self.drop_row(col)?;
+        self.set_piece_player(row, col, player);
+        Some(row)
+    }
+    // drop a player's letter into the given column, returns the row or None if the column is full
+    pub fn drop_letter(&mut self, col: usize, letter: String, player: Player) -> Option<usize> {
+        let row = self.drop_row(col)?;
+        self.set_piece_letter(row, col, letter);
+        self.set_piece_player(row, col, player);
+        Some(row)
+    }
+    // remove the bottom piece of the given column, the pieces above it fall down a row (for Pop Out)
+    pub fn pop_bottom(&mut self, col: usize) {
+        let column = &mut self.pieces[col - 1];
+        for i in (1..column.len()).rev() {
+            column[i].player = column[i - 1].player.take();
+            column[i].letter = column[i - 1].letter.take();
         }
-        return row_vec;
     }
-    // return vector of player ids in given col
-    pub fn get_ids_in_col(&self, col: usize) -> Vec<usize> {
-        let mut col_vec = vec![];
-        for row in 1..=self.num_rows {
-            match self.pieces[col - 1][row - 1].player.clone() {
-                None => col_vec.push(0),
-                Some(player) => col_vec.push(player.id),
-            }
+    // put the player's piece back under the given column, lifting the pieces in it up a row
+    // (undoes a pop), the column must not be full
+    pub fn push_bottom(&mut self, col: usize, player: Player) {
+        let column = &mut self.pieces[col - 1];
+        for i in 0..column.len() - 1 {
+            column[i].player = column[i + 1].player.take();
+            column[i].letter = column[i + 1].letter.take();
         }
-        return col_vec;
-    }
-    // return vector of player ids in right (/) diagonal from given row and col
-    pub fn get_ids_in_right_diagonal(&self, mut row: usize, mut col: usize) -> Vec<usize> {
-        // In right diagonal, up/right means R dec, C inc and down/left means R inc, C dec
-        // First, go to bottom left corner of diagonal, where row = 6 or col = 1
-        while row < self.num_rows && col > 1 { row += 1; col -= 1; }
-        // Now at leftmost of diagonal, iterate to rightmost of diagonal
-        let mut dia_vec = vec![];
-        while row >= 1 && col <= self.num_cols {
-            match self.pieces[col - 1][row - 1].player.clone() {
-                None => dia_vec.push(0),
-                Some(player) => dia_vec.push(player.id),
-            }
-            row -= 1; col += 1;
+        let bottom = column.len() - 1;
+        column[bottom].player = Some(player);
+    }
+    // empty the space at the given row and column
+    pub fn clear_piece(&mut self, row: usize, col: usize) {
+        self.pieces[col - 1][row - 1].player = None;
+        self.pieces[col - 1][row - 1].letter = None;
+    }
+    // remove the topmost piece of the given column (undoes a drop)
+    pub fn remove_top(&mut self, col: usize) {
+        let row = self.drop_row(col).map_or(1, |row| row + 1);
+        if row <= self.num_rows {
+            self.pieces[col - 1][row - 1].player = None;
+            self.pieces[col - 1][row - 1].letter = None;
         }
-        return dia_vec;
-    }
-    // return vector of player ids in left (\) diagonal from given row and col
-    pub fn get_ids_in_left_diagonal(&self, mut row: usize, mut col: usize) -> Vec<usize> {
-        // In left diagonal, up/left means R dec, C dec and down/right means R inc, C inc
-        // First, go to top left corner of diagonal, where row = 1 or col = 1
-        while row < 1 && col > 1 { row -= 1; col -= 1; }
-        // Now at leftmost of diagonal, iterate to rightmost of diagonal
-        let mut dia_vec = vec![];
-        while row <= self.num_rows && col <= self.num_cols {
-            match self.pieces[col - 1][row - 1].player.clone() {
-                None => dia_vec.push(0),
-                Some(player) => dia_vec.push(player.id),
-            }
-            row += 1; col += 1;
+    }
+    // return the (row, col) of every space on the line through the given row and col that
+    // runs in the given (row step, col step) direction, in the order of the direction.
+    // A row of a board that wraps has no ends, it starts at the given space and goes once round.
+    pub fn line_spaces(&self, row: usize, col: usize, (d_row, d_col): (isize, isize)) -> Vec<(usize, usize)> {
+        let (mut r, mut c) = (row as isize, col as isize);
+        if self.wraps && d_row == 0 {
+            return (0..self.num_cols as isize).filter_map(|i| self.space_at(r, c + d_col * i)).collect();
+        }
+        // First, back up to the first space of the line
+        while self.space_at(r - d_row, c - d_col).is_some() { r -= d_row; c -= d_col; }
+        // Now at the start of the line, step through to its last space
+        let mut spaces = vec![];
+        while let Some(space) = self.space_at(r, c) {
+            spaces.push(space);
+            r += d_row; c += d_col;
         }
-        return dia_vec;
+        spaces
+    }
+    // return the (row, col) of every space in the given row, left to right
+    pub fn row_spaces(&self, row: usize) -> Vec<(usize, usize)> {
+        self.line_spaces(row, 1, (0, 1))
+    }
+    // return the (row, col) of every space in the given col, top to bottom
+    pub fn col_spaces(&self, col: usize) -> Vec<(usize, usize)> {
+        self.line_spaces(1, col, (1, 0))
+    }
+    // return the (row, col) of every space in the right (/) diagonal through given row and col,
+    // from its bottom left to its top right
+    pub fn right_diagonal_spaces(&self, row: usize, col: usize) -> Vec<(usize, usize)> {
+        self.line_spaces(row, col, (-1, 1))
+    }
+    // return the (row, col) of every space in the left (\) diagonal through given row and col,
+    // from its top left to its bottom right
+    pub fn left_diagonal_spaces(&self, row: usize, col: usize) -> Vec<(usize, usize)> {
+        self.line_spaces(row, col, (1, 1))
+    }
+    // return the pieces of the given game at the given spaces
+    pub fn pieces_at<R: Rules>(&self, rules: &R, spaces: &[(usize, usize)]) -> Vec<Option<R::Piece>> {
+        spaces.iter().map(|&(row, col)| rules.piece_at(self, row, col)).collect()
+    }
+    // return vector of player ids at the given spaces, 0 for blank spaces
+    fn ids_at(&self, spaces: &[(usize, usize)]) -> Vec<usize> {
+        self.pieces_at(&Connect4, spaces).into_iter().map(|id| id.unwrap_or(0)).collect()
+    }
+    // return string of letters at the given spaces, '-' for blank spaces
+    fn letters_at(&self, spaces: &[(usize, usize)]) -> String {
+        self.pieces_at(&TootOtto::default(), spaces).into_iter().map(|letter| letter.unwrap_or('-')).collect()
+    }
+    // return vector of player ids in given row
+    pub fn get_ids_in_row(&self, row: usize) -> Vec<usize> {
+        self.ids_at(&self.row_spaces(row))
+    }
+    // return vector of player ids in given col
+    pub fn get_ids_in_col(&self, col: usize) -> Vec<usize> {
+        self.ids_at(&self.col_spaces(col))
+    }
+    // return vector of player ids in right (/) diagonal through given row and col
+    pub fn get_ids_in_right_diagonal(&self, row: usize, col: usize) -> Vec<usize> {
+        self.ids_at(&self.right_diagonal_spaces(row, col))
+    }
+    // return vector of player ids in left (\) diagonal through given row and col
+    pub fn get_ids_in_left_diagonal(&self, row: usize, col: usize) -> Vec<usize> {
+        self.ids_at(&self.left_diagonal_spaces(row, col))
     }
     // return string of letters in given row
     pub fn get_letters_in_row(&self, row: usize) -> String {
-        let mut row_str = String::new();
-        for col in 1..=self.num_cols {
-            match self.pieces[col - 1][row - 1].letter.clone() {
-                None => row_str.push_str("-"),  // for blank spaces
-                Some(letter) => row_str.push_str(&letter),
-            }
-        }
-        return row_str;
+        self.letters_at(&self.row_spaces(row))
     }
     // return string of letters in given col
     pub fn get_letters_in_col(&self, col: usize) -> String {
-        let mut col_str = String::new();
-        for row in 1..=self.num_rows {
-            match self.pieces[col - 1][row - 1].letter.clone() {
-                None => col_str.push_str("-"),  // for blank spaces
-                Some(letter) => col_str.push_str(&letter),
-            }
-        }
-        return col_str;
-    }
-    // return string of letters in right (/) diagonal from given row and col
-    pub fn get_letters_in_right_diagonal(&self, mut row: usize, mut col: usize) -> String {
-        // In right diagonal, up/right means R dec, C inc and down/left means R inc, C dec
-        // First, go to bottom left corner of diagonal, where row = 6 or col = 1
-        while row < self.num_rows && col > 1 { row += 1; col -= 1; }
-        // Now at leftmost of diagonal, iterate to rightmost of diagonal
-        let mut dia_str = String::new();
-        while row >= 1 && col <= self.num_cols {
-            match self.pieces[col - 1][row - 1].letter.clone() {
-                None => dia_str.push_str("-"),  // for blank spaces
-                Some(letter) => dia_str.push_str(&letter),
-            }
-            row -= 1; col += 1;
-        }
-        return dia_str;
-    }
-    // return string of letters in left (\) diagonal from given row and col
-    pub fn get_letters_in_left_diagonal(&self, mut row: usize, mut col: usize) -> String {
-        // In left diagonal, up/left means R dec, C dec and down/right means R inc, C inc
-        // First, go to top left corner of diagonal, where row = 1 or col = 1
-        while row < 1 && col > 1 { row -= 1; col -= 1; }
-        // Now at leftmost of diagonal, iterate to rightmost of diagonal
-        let mut dia_str = String::new();
-        while row <= self.num_rows && col <= self.num_cols {
-            match self.pieces[col - 1][row - 1].letter.clone() {
-                None => dia_str.push_str("-"),  // for blank spaces
-                Some(letter) => dia_str.push_str(&letter),
-            }
-            row += 1; col += 1;
-        }
-        return dia_str;
+        self.letters_at(&self.col_spaces(col))
+    }
+    // return string of letters in right (/) diagonal through given row and col
+    pub fn get_letters_in_right_diagonal(&self, row: usize, col: usize) -> String {
+        self.letters_at(&self.right_diagonal_spaces(row, col))
+    }
+    // return string of letters in left (\) diagonal through given row and col
+    pub fn get_letters_in_left_diagonal(&self, row: usize, col: usize) -> String {
+        self.letters_at(&self.left_diagonal_spaces(row, col))
     }
 }
 
@@ -215,996 +438,711 @@ pub fn notify(msg: String) {
 }
 
 #[wasm_bindgen]
-// set the game mode to "Toot-Otto" or "Connect4"
-pub fn set_game(game: String) {
-    GAME.set(game).unwrap();
+// set the game mode, e.g. GameMode.Connect4. set_variant picks it by id from available_variants instead
+pub fn set_game(game: GameMode) {
+    if GAME.is_set().unwrap() {
+        *GAME.write().unwrap() = game;
+    } else {
+        GAME.set(game).unwrap();
+    }
 }
 
 #[wasm_bindgen]
 // create a new board, must set the game mode first
+// The bots' random number generator starts from a fresh random seed, or the one set with
+// set_next_game_seed
 pub fn new_board() {
-    BOARD.set(Board::new()).unwrap();
+    start_game(Board::new());
 }
 
 #[wasm_bindgen]
-// For Connect4 game
-// Insert a piece into the selected column. Returns the row if successful, or 0 if the col is full
-pub fn insert_piece_C4(col: usize, player_id: usize) -> usize {
-    let mut board = BOARD.write().unwrap();
-    for row in (1..=board.num_rows).rev() { // starting from bottom
-        match board.get_piece(row, col).player {
-            Some(_) => {},
-            None => {
-                board.set_piece_player(row, col, Player{id: player_id});
-                println!("Changed piece R{}C{} to {:?}", row, col, board.get_piece(row, col));
-                return row
-            },
-        }
-    }
-    return 0
+// create a new board with the given number of rows and columns where win_len in a row wins,
+// throws if there are no rows or columns or win_len is under 2
+pub fn new_board_sized(num_rows: usize, num_cols: usize, win_len: usize) -> Result<(), JsValue> {
+    Board::check_size(num_rows, num_cols, win_len)?;
+    start_game(Board::with_size(num_rows, num_cols, win_len));
+    Ok(())
 }
 
 #[wasm_bindgen]
-// For Toot-Otto game
-// Insert a piece into the selected column. Returns the row if successful, or 0 if the col is full
-pub fn insert_piece_TO(col: usize, letter: String) -> usize {
-    let mut board = BOARD.write().unwrap();
-    for row in (1..=board.num_rows).rev() { // starting from bottom
-        match board.get_piece(row, col).letter {
-            Some(_) => {},
-            None => {
-                board.set_piece_letter(row, col, letter);
-                println!("Changed piece R{}C{} to {:?}", row, col, board.get_piece(row, col));
-                return row
-            },
-        }
-    }
-    return 0
+// create a new board with the given number of rows and columns whose left and right edges join,
+// so rows and diagonals wrap around it. Throws for the same sizes as new_board_sized
+pub fn new_board_cylinder(num_rows: usize, num_cols: usize, win_len: usize) -> Result<(), JsValue> {
+    Board::check_size(num_rows, num_cols, win_len)?;
+    start_game(Board::cylinder(num_rows, num_cols, win_len));
+    Ok(())
 }
 
-#[wasm_bindgen]
-// For Connect4 game
-// Check around a piece for a win, return true if the game has been won
-pub fn check_for_win_C4(row: usize, col: usize, player_id: usize) -> bool {
-    let board = BOARD.read().unwrap();
-
-    // Check for 4 in a row horizontally
-    let ids_row = board.get_ids_in_row(row);
-    if ids_row.iter().filter(|&x| *x == player_id).count() >= WIN_LEN_C4 {
-        let mut consec_cnt = 0;
-        for x in ids_row {
-            if x == player_id {
-                consec_cnt += 1;
-                if consec_cnt == WIN_LEN_C4 {
-                    return true;  // the player has won
-                }
-            } else {
-                consec_cnt = 0;
-            }
-        }
-    }
-    // Check for 4 in a row vertically
-    let ids_col = board.get_ids_in_col(col);
-    if ids_col.iter().filter(|&x| *x == player_id).count() >= WIN_LEN_C4 {
-        let mut consec_cnt = 0;
-        for x in ids_col {
-            if x == player_id {
-                consec_cnt += 1;
-                if consec_cnt == WIN_LEN_C4 {
-                    return true;  // the player has won
-                }
-            } else {
-                consec_cnt = 0;
-            }
-        }
-    }
-
-    // Check for 4 in a row diagonally /
-    let ids_r_dia = board.get_ids_in_right_diagonal(row, col);
-    if ids_r_dia.iter().filter(|&x| *x == player_id).count() >= WIN_LEN_C4 {
-        let mut consec_cnt = 0;
-        for x in ids_r_dia {
-            if x == player_id {
-                consec_cnt += 1;
-                if consec_cnt == WIN_LEN_C4 {
-                    return true;  // the player has won
-                }
-            } else {
-                consec_cnt = 0;
-            }
-        }
-    }
-
-    // Check for 4 in a row diagonally \
-    let ids_l_dia = board.get_ids_in_left_diagonal(row, col);
-    if ids_l_dia.iter().filter(|&x| *x == player_id).count() >= WIN_LEN_C4 {
-        let mut consec_cnt = 0;
-        for x in ids_l_dia {
-            if x == player_id {
-                consec_cnt += 1;
-                if consec_cnt == WIN_LEN_C4 {
-                    return true;  // the player has won
-                }
-            } else {
-                consec_cnt = 0;
-            }
-        }
-    }
+#[wasm_bindgen(unchecked_return_type = "BoardSize")]
+// get the size of the current board
+pub fn board_size() -> JsValue {
+    let playing = PLAYING.read().unwrap();
+    let board = playing.board();
+    to_js(&BoardSize{num_rows: board.num_rows, num_cols: board.num_cols, win_len: board.win_len, wraps: board.wraps})
+}
 
-    return false;
+#[wasm_bindgen(unchecked_return_type = "Board")]
+// get the whole board, every space with its player and letter
+pub fn get_board() -> JsValue {
+    to_js(PLAYING.read().unwrap().board())
 }
 
 #[wasm_bindgen]
-// For Toot-Otto game
-// Check around a piece for a win, return 1 for TOOT, 2 for OTTO, 0 if no win, 3 if tie
-pub fn check_for_win_TO(row: usize, col: usize) -> usize {
-    let board = BOARD.read().unwrap();
-    let mut winner = 0;
-
-    // Check for win horizontally
-    let letters_row = board.get_letters_in_row(row);
-    if letters_row.chars().filter(|x| *x != '-').count() >= WIN_LEN_C4 {
-        if letters_row.contains("TOOT") {
-            winner = 1;
-        } 
-        if letters_row.contains("OTTO") {
-            if winner != 1 {
-                winner = 2;
-            } else {
-                return 3;  // there was a tie
-            } 
-        }
-    }
-    // Check for win vertically
-    let letters_col = board.get_letters_in_col(col);
-    if letters_col.chars().filter(|x| *x != '-').count() >= WIN_LEN_C4 {
-        if letters_col.contains("TOOT") {
-            if winner != 2 {
-                winner = 1;
-            } else {
-                return 3;  // there was a tie
-            }
-        } else if letters_col.contains("OTTO") {
-            if winner != 1 {
-                winner = 2;
-            } else {
-                return 3;  // there was a tie
-            }
-        }
-    }
-
-    // Check for 4 in a row diagonally /
-    let letters_r_dia = board.get_letters_in_right_diagonal(row, col);
-    if letters_r_dia.chars().filter(|x| *x != '-').count() >= WIN_LEN_C4 {
-        if letters_r_dia.contains("TOOT") {
-            if winner != 2 {
-                winner = 1;
-            } else {
-                return 3;  // there was a tie
-            } 
-        } else if letters_r_dia.contains("OTTO") {
-            if winner != 1 {
-                winner = 2;
-            } else {
-                return 3;  // there was a tie
-            }
-        }
+// seed the random number generator the bots use, the same seed replays the same bot moves
+pub fn set_seed(seed: u32) {
+    let rng = ChaCha8Rng::seed_from_u64(seed as u64);
+    if SEED.is_set().unwrap() {
+        *SEED.write().unwrap() = seed;
+        *RNG.write().unwrap() = rng;
+    } else {
+        SEED.set(seed).unwrap();
+        RNG.set(rng).unwrap();
     }
+}
 
-    // Check for 4 in a row diagonally \
-    let letters_l_dia = board.get_letters_in_left_diagonal(row, col);
-    if letters_l_dia.chars().filter(|x| *x != '-').count() >= WIN_LEN_C4 {
-        if letters_l_dia.contains("TOOT") {
-            if winner != 2 {
-                winner = 1;
-            } else {
-                return 3;  // there was a tie
-            } 
-        } else if letters_l_dia.contains("OTTO") {
-            if winner != 1 {
-                winner = 2;
-            } else {
-                return 3;  // there was a tie
-            } 
-        }
+#[wasm_bindgen]
+// use the given seed for the next new board instead of a random one, to replay a recorded game.
+// Only that game uses it, the ones after it get random seeds again
+pub fn set_next_game_seed(seed: u32) {
+    if NEXT_SEED.is_set().unwrap() {
+        *NEXT_SEED.write().unwrap() = Some(seed);
+    } else {
+        NEXT_SEED.set(Some(seed)).unwrap();
     }
-
-    return winner;
 }
 
+// start a new game of the current game mode on the given board with player 1 to move. The bots
+// are seeded with the seed set for it, using it up, or a random one
+fn start_game(board: Board) {
+    // Five in a Row is played by the Connect4 rules, which go by the board's win length
+    let game: Box<dyn AnyGame> = match *GAME.read().expect("Game not defined before Board created!") {
+        GameMode::Connect4 | GameMode::FiveInARow => Box::new(PlayedGame::new(GameState::new(Connect4, board))),
+        GameMode::TootOtto => Box::new(PlayedGame::new(GameState::new(toot_otto(), board))),
+        GameMode::PopOut => Box::new(PlayedGame::new(GameState::new(PopOut, board))),
+        GameMode::PowerUp => Box::new(PlayedGame::new(GameState::new(PowerUp::default(), board))),
+    };
+    set_playing(game);
+    let requested = if NEXT_SEED.is_set().unwrap() { NEXT_SEED.write().unwrap().take() } else { None };
+    set_seed(requested.unwrap_or_else(|| rand::thread_rng().gen()));
+}
 
-// leaderboards stuff
+#[wasm_bindgen]
+// get the seed of the current game, record it with the game to replay the bots' moves
+pub fn get_seed() -> u32 {
+    *SEED.read().unwrap()
+}
 
-// leaderboards stuff
+// play the given game in place of the one being played
+fn set_playing(game: Box<dyn AnyGame>) {
+    if PLAYING.is_set().unwrap() {
+        *PLAYING.write().unwrap() = game;
+    } else {
+        PLAYING.set(game).unwrap();
+    }
+}
 
-// #[wasm_bindgen]
-// pub fn getUsername(username: &JsValue) -> JsValue {
-    
-//     let mut doc_name_t: String = username.as_string().unwrap();
+// Run f on the game being played if it is played by the rules R, None if it is not
+fn with_game<R: Rules + 'static, T>(f: impl FnOnce(&mut PlayedGame<R>) -> T) -> Option<T> {
+    PLAYING.write().unwrap().as_any_mut().downcast_mut::<PlayedGame<R>>().map(f)
+}
 
-//     let mut doc_name = doc_name_t.as_str();
+// Drop a piece of the given player into the selected column of a game played by the rules R and
+// add it to the history. Returns the row if successful, or 0 if the col is full, the player
+// has no such piece left or the game is not played by R
+fn insert_piece<R: Rules + 'static>(col: usize, player_id: usize, piece: R::Piece, by_bot: bool) -> usize {
+    with_game(|game: &mut PlayedGame<R>| game.play(col, player_id, piece, by_bot)).flatten().unwrap_or(0)
+}
 
-//     if doc_name == "" {
-//         return JsValue::from_str("[please enter a valid username]");
-//     } else {
-//         return JsValue::from_str(doc_name);
-//     }
-    
-// }
+#[wasm_bindgen]
+// For Connect4 and Five in a Row games
+// Insert a piece into the selected column. Returns the row if successful, or 0 if the col is full
+#[allow(non_snake_case)]
+pub fn insert_piece_C4(col: usize, player_id: usize) -> usize {
+    insert_piece::<Connect4>(col, player_id, player_id, false)
+}
 
-// #[wasm_bindgen]
-// pub fn getPassword(password: &JsValue) -> JsValue {
-    
-//     let mut doc_password_t: String = password.as_string().unwrap();
+#[wasm_bindgen]
+// For Toot-Otto game
+// Insert a letter of the given player into the selected column. Returns the row if successful,
+// or 0 if the col is full or the player has no more of the letter
+#[allow(non_snake_case)]
+pub fn insert_piece_TO(col: usize, letter: String, player_id: usize) -> usize {
+    match letter.chars().next() {
+        Some(letter) => insert_piece::<TootOtto>(col, player_id, letter, false),
+        None => 0,
+    }
+}
 
-//     let mut doc_password = doc_password_t.as_str();
+#[wasm_bindgen]
+// For Pop Out game
+// Insert a piece into the selected column. Returns the row if successful, or 0 if the col is full
+#[allow(non_snake_case)]
+pub fn insert_piece_PO(col: usize, player_id: usize) -> usize {
+    insert_piece::<PopOut>(col, player_id, PopOutMove::Drop, false)
+}
 
-//     if doc_password == "" {
-//         return JsValue::from_str("[please enter a valid password]");
-//     } else {
-//         return JsValue::from_str(doc_password);
-//     }
-    
-// }
+#[wasm_bindgen]
+// For Pop Out game
+// Pop the player's piece off the bottom of the selected column, the pieces above it fall a row.
+// Returns true if successful, false if the bottom piece is not the player's
+#[allow(non_snake_case)]
+pub fn pop_piece_PO(col: usize, player_id: usize) -> bool {
+    insert_piece::<PopOut>(col, player_id, PopOutMove::Pop, false) != 0
+}
 
+#[wasm_bindgen]
+// For Pop Out game
+// return true if the bottom piece of the selected column is the player's
+#[allow(non_snake_case)]
+pub fn can_pop_PO(col: usize, player_id: usize) -> bool {
+    PopOut.can_pop(PLAYING.read().unwrap().board(), col, player_id)
+}
 
+#[wasm_bindgen]
+// For Pop Out game
+// Check the selected column for a win after the given player dropped or popped in it,
+// return the id of the winner, 0 if no win, 3 if the game is drawn
+#[allow(non_snake_case)]
+pub fn check_for_win_PO(col: usize, player_id: usize) -> usize {
+    let playing = PLAYING.read().unwrap();
+    let board = playing.board();
+    // Pop Out looks for lines through the whole column, so any row will do
+    match PopOut.outcome(board, 1, col, player_id) {
+        Some(OutcomeC4::Win(id)) => id,
+        Some(OutcomeC4::Draw) => 3,
+        None => 0,
+    }
+}
 
-// https://stackoverflow.com/questions/47529643/how-to-return-a-string-or-similar-from-rust-in-webassembly#:~:text=You%20cannot%20directly%20return%20a,string%20on%20the%20JavaScript%20side.
 #[wasm_bindgen]
-pub fn showC4Leaderboard() -> JsValue {
-    let mut c4List = "".to_owned();
-    for i in 0..5 {
-        let tempStr: String = ". [USER]\n".to_owned();
-        c4List.push_str(&(i+1).to_string());
-        c4List.push_str(&tempStr);
-        // c4List += i as String;
+// For Power-Up game
+// Play a "disc", "anvil", "bomb" or "wall" of the given player into the selected column. Returns the
+// row it ended up in, or 0 if the player has no such piece left or it cannot be played in the col
+#[allow(non_snake_case)]
+pub fn insert_piece_PU(col: usize, player_id: usize, power: String) -> usize {
+    match Power::from_name(&power) {
+        Some(power) => insert_piece::<PowerUp>(col, player_id, power, false),
+        None => 0,
     }
-    let c4ListStr = c4List.as_str();
+}
 
-    return JsValue::from_str(c4ListStr);
+#[wasm_bindgen(unchecked_return_type = "Power[]")]
+// For Power-Up game
+// Get the power pieces the given player has not played yet
+#[allow(non_snake_case)]
+pub fn powers_left_PU(player_id: usize) -> JsValue {
+    let left = with_game(|game: &mut PlayedGame<PowerUp>| game.state.rules.powers_left(player_id));
+    to_js(&left.unwrap_or_default())
+}
+
+#[wasm_bindgen(unchecked_return_type = "MovePU[]")]
+// For Power-Up game
+// Get the anvils, bombs and walls on the board. The board has them as pieces of the player who
+// played them, and walls as pieces of id 3, which belong to neither player
+#[allow(non_snake_case)]
+pub fn powers_on_board_PU() -> JsValue {
+    let powers = with_game(|game: &mut PlayedGame<PowerUp>| {
+        game.state.rules.powers_on_board().iter().map(|&(row, col, power)| MovePU{row, col, power}).collect::<Vec<MovePU>>()
+    });
+    to_js(&powers.unwrap_or_default())
 }
 
 #[wasm_bindgen]
-//Easy bot for otto game
-pub fn easy_otto()-> Array{
-    loop{
-        let mut rng = rand::thread_rng();
-        let letter_choice = if rng.gen_bool(0.5) { 'T' } else { 'O' };
-        let column = rng.gen_range(1..6);
-        
-       
-        let insert_obj = insert_piece_TO(column, letter_choice.to_string());
-
-         if insert_obj>0 && insert_obj>0 {
-            let arr = Array::new();
-    
-        arr.push(&JsValue::from(insert_obj));
-        arr.push(&JsValue::from(column));
-        arr.push(&JsValue::from(letter_choice.to_string()));
-        return arr;
-        }
+// For Power-Up game
+// Check the selected column for a win after the given player played in it,
+// return the id of the winner, 0 if no win, 3 if the game is drawn
+#[allow(non_snake_case)]
+pub fn check_for_win_PU(col: usize, player_id: usize) -> usize {
+    let playing = PLAYING.read().unwrap();
+    let board = playing.board();
+    // the piece played is the top one of its column, whatever row is given
+    match PowerUp::default().outcome(board, 1, col, player_id) {
+        Some(OutcomeC4::Win(id)) => id,
+        Some(OutcomeC4::Draw) => 3,
+        None => 0,
+    }
+}
 
-        let win = check_for_win_TO(insert_obj, column);
-        if win == 2 {
-            alert(format!("Player 2 has won!!!", ).as_str());
+// convert spelled words to Words for JS, with the id of the player who owns each
+fn words_to_js(rules: &TootOtto, words: Vec<evaluate::WordTO>) -> JsValue {
+    to_js(&words.iter().map(|word| Word::new(rules, word)).collect::<Vec<Word>>())
+}
 
-        }
+#[wasm_bindgen(unchecked_return_type = "Word[]")]
+// For Toot-Otto game
+// Get every word spelled by the piece at the given row and col, with the id of the player
+// who owns the word, so the winning letters can be highlighted
+#[allow(non_snake_case)]
+pub fn winning_words_TO(row: usize, col: usize) -> JsValue {
+    let playing = PLAYING.read().unwrap();
+    let board = playing.board();
+    words_to_js(&toot_otto(), evaluate::words_through_TO(board, row, col))
+}
 
-    }
+#[wasm_bindgen(unchecked_return_type = "Word[]")]
+// For Toot-Otto game
+// Get every word spelled anywhere on the board, in the same form as winning_words_TO
+#[allow(non_snake_case)]
+pub fn words_on_board_TO() -> JsValue {
+    let playing = PLAYING.read().unwrap();
+    let board = playing.board();
+    words_to_js(&toot_otto(), evaluate::words_TO(board))
 }
 
-//This function evaluates the board
 #[wasm_bindgen]
-pub fn evaluate_TO(player_id: usize) -> Array{
-    let board = BOARD.read().unwrap();
-     let mut horizontal_score = 0;
-     let mut vertical_score = 0;
-     let mut diagonal_score = 0;
-     let mut rev_diagonal_score = 0;
-    
-   
-
-    for row in (1..TO_NUM_ROWS+1).rev() {
-        for column in 1..TO_NUM_COLS+1{
-            
-            for i in 0..3{
-              
-                if column <= TO_NUM_COLS-3{
-                   
-                        match board.get_piece(row, column+i).player{
-                            Some(player) => {
-                             
-                                if player.id == player_id{
-                                horizontal_score += 1;
-                              
-                                break;
-                                }
-                            }            
-                            None => {
-                       
-                                break; 
-                            }  
-                        }
-                }
-              
-
-        
-                //Count number of consecutive vertically
-                if row >= TO_NUM_ROWS-3{    
-                    
-                    match board.get_piece(row-i, column).player{
-                        Some(player) => {
-                            if player.id == player_id{
-                                vertical_score += 1;
-                                break;
-                    
-                            }
-                    }
-                        None => {
-                            
-                            break; 
-                        } 
-   
-                    }
-
-                    }
-            
-                //Diagonal count
-                if row >= TO_NUM_ROWS-2 && column <= TO_NUM_COLS-3{
-                   
-                    match board.get_piece(row-i, column+i).player{
-                        Some(player) => {
-                        
-                            if player.id == player_id{
-                            diagonal_score += 1;
-                            break;
-                        }
-  
-                            }
-                        
-                        None => {
-                        
-                            break; 
-                        } 
-
-                    }
-                }
-               
-                //Reverse Diagonal
-                if row >= 3 && column >= TO_NUM_COLS-3{
-                  
-                    match board.get_piece(row-i, column-i).player{
-                        Some(player) => {
-
-                        if player.id == player_id{
-                        rev_diagonal_score += 1;
-                        break;
-                        }
-                         
-                    }
-                    None => {
-                        
-                        break; 
-                    } 
-                    }
-
-                }
-            }
-        }
-        
-    }
-    let mut win = 0;
-    if (horizontal_score == 4){
-        win = horizontal_score;
-    }
-    else if (vertical_score == 4){
-        win = vertical_score;
+// For Toot-Otto game
+// Make the given player spell TOOT and the other player OTTO, player 1 spells TOOT until this is called.
+// Throws unless the player is 1 or 2
+pub fn set_toot_player(player_id: usize) -> Result<(), JsValue> {
+    if player_id != 1 && player_id != 2 {
+        return Err(JsValue::from(format!("TOOT must be spelled by player 1 or 2, not {}", player_id)));
     }
-    else if (diagonal_score == 4){
-        win = diagonal_score;
+    if TOOT_PLAYER.is_set().unwrap() {
+        *TOOT_PLAYER.write().unwrap() = player_id;
+    } else {
+        TOOT_PLAYER.set(player_id).unwrap();
     }
-    else if(rev_diagonal_score == 4){
-        win = rev_diagonal_score;
+    if PLAYING.is_set().unwrap() {
+        with_game(|game: &mut PlayedGame<TootOtto>| game.state.rules = toot_otto());
     }
-    let total_score = horizontal_score+vertical_score+diagonal_score+rev_diagonal_score;
-    let array = Array::new_with_length(2);
-    
-        array.push(&JsValue::from(win));
-        array.push(&JsValue::from(total_score));
-        let mut column = 0;
-        let mut  insert_obj = 0;
-        
-         
-    return array;
-      
-       
-    
-   
-            
+    Ok(())
 }
 
 #[wasm_bindgen]
+// For Toot-Otto game
+// Get the word the given player spells, "TOOT" or "OTTO"
+pub fn word_of(player_id: usize) -> String {
+    toot_otto().word(player_id).to_string()
+}
 
-//Added by AB
-pub fn difficult_TO(player_id: usize) -> Array{   
-    loop{
-        let mut rng = rand::thread_rng();
-        let letter_choice = if rng.gen_bool(0.5) { 'T' } else { 'O' };
-        let column = rng.gen_range(1..6);
-        
-        
-        let insert_obj = insert_piece_TO(column, letter_choice.to_string());
-       
-
-         if insert_obj>0 && insert_obj>0 {
-            let arr = Array::new();
-    
-            arr.push(&JsValue::from(insert_obj));
-            arr.push(&JsValue::from(column));
-            arr.push(&JsValue::from(letter_choice.to_string()));
-            return arr;
-        }
-
+// the Toot-Otto rules with the words bound to the players by set_toot_player
+fn toot_otto() -> TootOtto {
+    if TOOT_PLAYER.is_set().unwrap() {
+        TootOtto::new(*TOOT_PLAYER.read().unwrap())
+    } else {
+        TootOtto::default()
     }
 }
-#[wasm_bindgen]
-
-pub fn value_TO( player_id: usize, depth: i32, alpha: f64, beta: f64) -> JsValue{
-   
-    let mut score = evaluate_TO(player_id);
-    if depth>=4{
-        let mut value = 0.0; 
 
-        let mut win = score.get(0).as_f64().unwrap();
-        let mut total_score = score.get(1).as_f64().unwrap();
+#[wasm_bindgen(unchecked_return_type = "LettersLeft")]
+// For Toot-Otto game
+// Get the number of T's and O's the given player has left
+pub fn letters_left(player_id: usize) -> JsValue {
+    let playing = PLAYING.read().unwrap();
+    let board = playing.board();
+    let (t, o) = toot_otto().letters_left(board, player_id);
+    to_js(&LettersLeft{t, o})
+}
 
-        value = total_score;
+#[wasm_bindgen]
+// For Connect4 game
+// Check around a piece for a win, return true if the game has been won
+#[allow(non_snake_case)]
+pub fn check_for_win_C4(row: usize, col: usize, player_id: usize) -> bool {
+    let playing = PLAYING.read().unwrap();
+    let board = playing.board();
+    matches!(Connect4.outcome(board, row, col, player_id), Some(OutcomeC4::Win(id)) if id == player_id)
+}
 
-       
-       if win == 4.0 && player_id == 2 {
-        value = 999999.0;
-       }
-       else if (win == 4.0 && player_id == 1){
-        value = 999999.0*-1.0;
-       }
-       
-       if depth %2 == 0 {
-        return minState_TO(player_id,depth+1 , alpha, beta);
-       }
-       return maxState_TO(player_id, depth, alpha, beta);
+#[wasm_bindgen(unchecked_return_type = "Line[]")]
+// For Connect4 game
+// Get the lines the piece at the given row and col won with
+#[allow(non_snake_case)]
+pub fn winning_lines_C4(row: usize, col: usize) -> JsValue {
+    let playing = PLAYING.read().unwrap();
+    let board = playing.board();
+    let lines: Vec<Line> = evaluate::lines_through_C4(board, row, col).iter().map(Line::new).collect();
+    to_js(&lines)
+}
 
+#[wasm_bindgen]
+// For Toot-Otto game
+// Check around a piece for a win, return the id of the player whose word was spelled,
+// 0 if no win, 3 if tie
+#[allow(non_snake_case)]
+pub fn check_for_win_TO(row: usize, col: usize) -> usize {
+    let playing = PLAYING.read().unwrap();
+    let board = playing.board();
+    let rules = toot_otto();
+    match rules.outcome(board, row, col, board.player_at(row, col).unwrap_or(0)) {
+        Some(OutcomeTO::Tie) => 3,
+        Some(outcome) => rules.winner(outcome).unwrap_or(0),
+        None => 0,
     }
+}
 
-    else{
-        return maxState_TO(player_id, depth, alpha, beta);
-     
-    
-    }
 
+#[wasm_bindgen(unchecked_return_type = "Cell[]")]
+// Take back the last human move and the bot moves that answered it, so it is a human's turn again
+// Returns every space cleared, last played first. A pop, anvil or bomb taken back changes its
+// whole column and is left out, so for Pop Out and Power-Up redraw the board from get_board
+pub fn undo() -> JsValue {
+    to_js(&PLAYING.write().unwrap().take_back())
+}
 
+#[wasm_bindgen(unchecked_return_type = "Placed[]")]
+// Play again the moves taken back by the last undo
+// Returns the piece in every space filled, first played first. Pops, anvils and bombs are left
+// out, so for Pop Out and Power-Up redraw the board from get_board
+pub fn redo() -> JsValue {
+    to_js(&PLAYING.write().unwrap().play_again())
 }
 
 #[wasm_bindgen]
-pub fn choose_TO(choice: Vec<i32>) -> i32 {
-    let mut index = rand::thread_rng().gen_range(0..choice.len());
-    return choice[index]
+// true if the last move played finished the game, undo clears it
+pub fn is_game_over() -> bool {
+    PLAYING.read().unwrap().is_game_over()
 }
 
 #[wasm_bindgen]
-pub fn maxState_TO(player_id: usize, depth: i32, alpha: f64, beta: f64) -> JsValue {
-    let mut val = -100000000007.0;
-    let mut play = -1;
-    let mut tempValue = Array::new();
-    let mut tempState = 0;
-    let mut moves = Array::new();
-
-    let mut new_board = BOARD.write().unwrap();
-
-    for i in (0..6) {
-        // Insert those pieces in the cloned board new_board.set_piece(row, col, player)
-        if tempState > 0 {
-           // tempValue = value(tempState, 2, depth, alpha, beta);
-            let temp = tempValue.get(0).as_f64().unwrap();
-            if temp > val {
-                val = temp;
-                play = i;
-                moves.push(&JsValue::from(i));
-            } else if temp == val {
-                moves.push(&JsValue::from(i));
-            }
-
-            if val > beta {
-                let vec = moves.to_vec();
-                let js_values = vec![JsValue::from(1), JsValue::from(2), JsValue::from(3)];
-                let i32_values: Vec<i32> =
-                    js_values.iter().map(|value| value.as_f64().unwrap() as i32).collect();
-
-                play = choose_TO(i32_values);
-                let result = Array::new();
-                result.push(&JsValue::from(val));
-                result.push(&JsValue::from(play));
-                return result.into();
-            }
-            let mut alpha = 0.0;
-            if val > alpha {
-                alpha = val;
-            }
-        }
-    }
-    let js_values = vec![JsValue::from(1), JsValue::from(2), JsValue::from(3)];
-    let i32_values: Vec<i32> = js_values.iter().map(|value| value.as_f64().unwrap() as i32).collect();
-
-    play = choose_TO(i32_values).into();
-    let result = Array::new();
-    result.push(&JsValue::from(val));
-    result.push(&JsValue::from(play));
-    return result.into();
+// number of moves on the board since the game started or its position was loaded
+pub fn moves_played() -> usize {
+    PLAYING.read().unwrap().moves_played()
 }
 
 #[wasm_bindgen]
-pub fn minState_TO(player_id: usize, depth: i32, alpha: f64, beta: f64) -> JsValue{
-    let mut val = -100000000007.0;
-    let mut play = -1;
-    let mut tempValue = Array::new();
-    let mut tempState = 0.0;
-    let mut moves = Array::new();
-
-    for i in (0..7) {
-        // Insert those pieces in the cloned board new_board.set_piece(row, col, player)
-        if tempState > 0.0 {
-          //  tempValue = value(tempState, 2, depth, alpha, beta);
-            let temp = tempValue.get(0).as_f64().unwrap();
-            if temp < val {
-                val = temp;
-                play = i;
-                moves.push(&JsValue::from(i));
-            } else if temp == val {
-                moves.push(&JsValue::from(i));
-            }
-            
-
-            if val < alpha {
-                let vec = moves.to_vec();
-                let js_values = vec![JsValue::from(1), JsValue::from(2), JsValue::from(3)];
-                let i32_values: Vec<i32> =
-                    js_values.iter().map(|value| value.as_f64().unwrap() as i32).collect();
-
-                play = choose_TO(i32_values);
-                let result = Array::new();
-                result.push(&JsValue::from(val));
-                result.push(&JsValue::from(play));
-                return result.into();
-            }
-            let mut alpha = 0.0;
-            if val > beta {
-                alpha = val;
-            }
-        }
-    }
-
-    let js_values = vec![JsValue::from(1), JsValue::from(2), JsValue::from(3)];
-    let i32_values: Vec<i32> = js_values.iter().map(|value| value.as_f64().unwrap() as i32).collect();
+pub fn can_undo() -> bool {
+    PLAYING.read().unwrap().can_undo()
+}
 
-    play = choose_TO(i32_values).into();
-    let result = Array::new();
-    result.push(&JsValue::from(val));
-    result.push(&JsValue::from(play));
-    return result.into();
-            
+#[wasm_bindgen]
+pub fn can_redo() -> bool {
+    PLAYING.read().unwrap().can_redo()
+}
 
+// Replace the game played by the rules R with the given moves, after checking them by replaying
+// them on an empty board of the same size. Returns the (row, col) every move landed in
+fn load_moves<R: Rules + Clone + 'static>(moves: &[(usize, R::Piece)]) -> Result<Vec<(usize, usize)>, String> {
+    let loaded = with_game(|game: &mut PlayedGame<R>| {
+        let empty = game.state.board.cleared();
+        notation::replay(game.state.rules.clone(), empty.clone(), moves)?;
+        *game = PlayedGame::new(GameState::new(game.state.rules.clone(), empty));
+        let spaces = moves.iter().enumerate()
+            .map(|(i, &(col, piece))| (game.play(col, 1 + i % 2, piece, false).unwrap_or(0), col))
+            .collect();
+        Ok(spaces)
+    });
+    loaded.unwrap_or_else(|| Err("the moves are not of the game being played".to_string()))
+}
 
+#[wasm_bindgen]
+// For Connect4 game
+// Get the moves played so far in column-sequence notation, e.g. "4453323"
+// Throws if a piece was popped or a power piece played, only drops can be written in it
+#[allow(non_snake_case)]
+pub fn game_notation_C4() -> Result<String, JsValue> {
+    let cols = PLAYING.read().unwrap().dropped_cols()
+        .ok_or("only dropped pieces can be written in column-sequence notation")?;
+    Ok(notation::write_C4(&cols)?)
+}
 
+#[wasm_bindgen(unchecked_return_type = "Placed[]")]
+// For Connect4 game
+// Replace the game with the one in column-sequence notation, throws if a move is not legal
+// Returns every piece, first played first
+#[allow(non_snake_case)]
+pub fn load_notation_C4(notation: String) -> Result<JsValue, JsValue> {
+    let moves = notation::moves_C4(&notation)?;
+    let spaces = load_moves::<Connect4>(&moves)?;
+
+    let placed: Vec<Placed> = spaces.iter().zip(moves.iter())
+        .map(|(&(row, col), &(_, player_id))| Placed{row, col, piece: js::Piece::Player(player_id)})
+        .collect();
+    Ok(to_js(&placed))
 }
 
 #[wasm_bindgen]
+// For Toot-Otto game
+// Get the game so far in notation, with the given player names and the words and result as headers
+// Throws if the game is not Toot-Otto
+#[allow(non_snake_case)]
+pub fn game_notation_TO(player1: String, player2: String) -> Result<String, JsValue> {
+    let record = with_game(|game: &mut PlayedGame<TootOtto>| notation::RecordTO{
+        players: [player1, player2],
+        toot_player: game.state.rules.toot_player(),
+        result: game.result(),
+        moves: game.moves(),
+    });
+    let record = record.ok_or("the game is not a Toot-Otto game")?;
+    Ok(notation::write_TO(&record))
+}
 
-pub fn medium_TO(player_id: usize) -> Array{
-    let mut column = 0;
-    let mut  row = 0;
-    let mut data = 0;
-  
-   // let board = BOARD.read().unwrap();
-
-   loop{
-    let mut rng = rand::thread_rng();
-    let letter_choice = if rng.gen_bool(0.5) { 'T' } else { 'O' };
-    let column = rng.gen_range(1..6);
-    let insert_obj = insert_piece_TO(column, letter_choice.to_string());
-   
-     if insert_obj>0 && insert_obj>0 {
-        let arr = Array::new();
+#[wasm_bindgen(unchecked_return_type = "Placed[]")]
+// For Toot-Otto game
+// Replace the game with the one in notation, binding the words as its Toot header says.
+// Throws if a move is not legal. Returns every piece, first played first
+#[allow(non_snake_case)]
+pub fn load_notation_TO(notation: String) -> Result<JsValue, JsValue> {
+    let record = notation::parse_TO(&notation)?;
+    let empty = PLAYING.read().unwrap().board().cleared();
+    record.game(empty)?;
+    set_toot_player(record.toot_player)?;
+    let spaces = load_moves::<TootOtto>(&record.moves)?;
+
+    let placed: Vec<Placed> = spaces.iter().zip(record.moves.iter())
+        .map(|(&(row, col), &(_, letter))| Placed{row, col, piece: js::Piece::Letter(letter)})
+        .collect();
+    Ok(to_js(&placed))
+}
 
-        arr.push(&JsValue::from(insert_obj));
-        arr.push(&JsValue::from(column));
-        arr.push(&JsValue::from(letter_choice.to_string()));
-        return arr;
-    }
+#[wasm_bindgen]
+// Get the position on the board as a string, see notation::to_position_string for the format.
+// Five in a Row is written as Connect4 on its board. Throws for Power-Up, which has no position strings
+pub fn to_position_string() -> Result<String, JsValue> {
+    let game = PLAYING.read().unwrap().position();
+    let mode = *GAME.read().expect("Game not defined before Board created!");
+    let game = game.ok_or(format!("{} positions cannot be written as a string", mode.name()))?;
+    Ok(notation::to_position_string(&game))
+}
 
+#[wasm_bindgen(unchecked_return_type = "Placed[]")]
+// Replace the board with the position in the string, which must be of the current game mode.
+// Moves before the position cannot be undone. Throws if the position is not valid
+// Returns every piece on the board
+pub fn from_position_string(position: String) -> Result<JsValue, JsValue> {
+    let game = notation::from_position_string(&position)?;
+    let placed = Placed::all(game.board());
+    let mode = *GAME.read().expect("Game not defined before Board created!");
+    let game: Box<dyn AnyGame> = match (game, mode) {
+        (notation::Game::PopOut(game), GameMode::PopOut) => Box::new(PlayedGame::new(game)),
+        (notation::Game::Connect4(game), GameMode::Connect4 | GameMode::FiveInARow) => Box::new(PlayedGame::new(game)),
+        (notation::Game::TootOtto(game), GameMode::TootOtto) => {
+            set_toot_player(game.rules.toot_player())?;
+            Box::new(PlayedGame::new(game))
+        },
+        _ => return Err(JsValue::from(format!("the position is not a {} position", mode.name()))),
+    };
+    set_playing(game);
+    Ok(to_js(&placed))
 }
-    
-    
+
+#[wasm_bindgen]
+// id of the player to move, the one who did not play the last move
+pub fn player_to_move() -> usize {
+    PLAYING.read().unwrap().to_move()
 }
 
+#[wasm_bindgen(unchecked_return_type = "RecordResult")]
+// result of the game on the board, Unfinished until the last move played ends it
+pub fn game_result() -> JsValue {
+    to_js(&PLAYING.read().unwrap().result())
+}
 
-//---------------------------------------------------C4 GAME --------------------------------------
+// leaderboards stuff
 
-#[wasm_bindgen]
+// leaderboards stuff
 
-//Added by AB
-pub fn easy_bot_C4(player_id: usize) -> Array{
-    
-    let mut column = 0;
-    let mut  insert_obj = 0;
-    
-     loop{
-        let mut rng = rand::thread_rng();
-        let column = rng.gen_range(1..7);
-      
-        let insert_obj = insert_piece_C4(column, player_id);
-      
-         if insert_obj>0 && insert_obj>0 {
-            let arr = Array::new();
+// #[wasm_bindgen]
+// pub fn getUsername(username: &JsValue) -> JsValue {
     
-            arr.push(&JsValue::from(insert_obj));
-            arr.push(&JsValue::from(column));
-            return arr;
-         }
+//     let mut doc_name_t: String = username.as_string().unwrap();
 
-        let win = check_for_win_C4(insert_obj, column, player_id);
-        if win == true {
-            alert(format!("Player 2 has won!!!", ).as_str());
+//     let mut doc_name = doc_name_t.as_str();
 
-        }
+//     if doc_name == "" {
+//         return JsValue::from_str("[please enter a valid username]");
+//     } else {
+//         return JsValue::from_str(doc_name);
+//     }
+    
+// }
 
-    }
+// #[wasm_bindgen]
+// pub fn getPassword(password: &JsValue) -> JsValue {
     
-}
+//     let mut doc_password_t: String = password.as_string().unwrap();
 
-#[wasm_bindgen]
+//     let mut doc_password = doc_password_t.as_str();
 
-//Added by AB
-pub fn evaluate_C4(player_id: usize) -> Array{
-    let board = BOARD.read().unwrap();
-    let mut horizontal_score = 0;
-    let mut vertical_score = 0;
-    let mut diagonal_score = 0;
-    let mut rev_diagonal_score = 0;
-    let arr = easy_bot_C4(2);
-   
-
-    for row in (1..C4_NUM_ROWS+1).rev() {
-        for column in 1..C4_NUM_COLS+1{
-            
-            for i in 0..3{
-               
-                if column <= C4_NUM_COLS-3{
-                   
-                        match board.get_piece(row, column+i).player{
-                            Some(player) => {
-                             
-                                if player.id == player_id{
-                                horizontal_score += 1;
-                              
-                                break;
-                                }
-                            }            
-                            None => {
-                       
-                                break; 
-                            }  
-                        }
-                }
-        
-                //Count number of consecutive vertically
-                if row >= C4_NUM_ROWS-3{    
-                    //alert(format!("Reached vertical row and column are {row}{column}", ).as_str());
-                    match board.get_piece(row-i, column).player{
-                        Some(player) => {
-                            if player.id == player_id{
-                                vertical_score += 1;
-                                break;
-                    
-                            }
-                    }
-                        None => {
-                            
-                            break; 
-                        } 
-   
-                    }
-
-                    }
-            
-                //Diagonal count
-                if row >= C4_NUM_ROWS-2 && column <= C4_NUM_COLS-3{
-                   // alert(format!("Reached diagonal row and column are {row}{column}", ).as_str());
-                    match board.get_piece(row-i, column+i).player{
-                        Some(player) => {
-                        
-                            if player.id == player_id{
-                            diagonal_score += 1;
-                            break;
-                        }
-  
-                            }
-                        
-                        None => {
-                        
-                            break; 
-                        } 
-
-                    }
-                }
-               
-                //Reverse Diagonal
-                if row >= 3 && column >= C4_NUM_COLS-3{
-                  //  alert(format!("Reached rev diagonal row and column are {row}{column}", ).as_str());
-                    match board.get_piece(row-i, column-i).player{
-                        Some(player) => {
-
-                        if player.id == player_id{
-                        rev_diagonal_score += 1;
-                        break;
-                        }
-                         
-                    }
-                    None => {
-                        
-                        break; 
-                    } 
-                    }
-
-                }
-            }
-        }
-        
-    }
-    let mut win = 0;
-    if (horizontal_score == 4){
-        win = horizontal_score;
-    }
-    else if (vertical_score == 4){
-        win = vertical_score;
-    }
-    else if (diagonal_score == 4){
-        win = diagonal_score;
-    }
-    else if(rev_diagonal_score == 4){
-        win = rev_diagonal_score;
-    }
-    let total_score = horizontal_score+vertical_score+diagonal_score+rev_diagonal_score;
-    let array = Array::new_with_length(2);
-    
-        array.push(&JsValue::from(win));
-        array.push(&JsValue::from(total_score));
-        let mut column = 0;
-        let mut  insert_obj = 0;
-        
-         
-    return array;
-       
-       
+//     if doc_password == "" {
+//         return JsValue::from_str("[please enter a valid password]");
+//     } else {
+//         return JsValue::from_str(doc_password);
+//     }
     
-   
-            
-}
+// }
 
-#[wasm_bindgen]
 
-//Added by AB
-pub fn difficult_C4(player_id: usize) -> Array{   
-  
-   let mut column = 0;
-   let mut  insert_obj = 0;
-   
-    loop{
-       let mut rng = rand::thread_rng();
-       let column = rng.gen_range(1..7);
-     
-       let insert_obj = insert_piece_C4(column, player_id);
-
-        if insert_obj>0 && insert_obj>0 {
-           let arr = Array::new();
-   
-       arr.push(&JsValue::from(insert_obj));
-       arr.push(&JsValue::from(column));
-       return arr;
-        }
 
-       let win = check_for_win_C4(insert_obj, column, player_id);
-       if win == true {
-           alert(format!("Player 2 has won!!!", ).as_str());
+// https://stackoverflow.com/questions/47529643/how-to-return-a-string-or-similar-from-rust-in-webassembly#:~:text=You%20cannot%20directly%20return%20a,string%20on%20the%20JavaScript%20side.
+#[wasm_bindgen]
+#[allow(non_snake_case)]
+pub fn showC4Leaderboard() -> JsValue {
+    let mut c4List = "".to_owned();
+    for i in 0..5 {
+        let tempStr: String = ". [USER]\n".to_owned();
+        c4List.push_str(&(i+1).to_string());
+        c4List.push_str(&tempStr);
+        // c4List += i as String;
+    }
+    let c4ListStr = c4List.as_str();
 
-       }
+    return JsValue::from_str(c4ListStr);
+}
 
-   }
+#[wasm_bindgen(unchecked_return_type = "Placed")]
+//Easy bot for otto game, plays as player 2
+pub fn easy_otto()-> JsValue{
+    bot_move_TO(2, &BotConfig::easy())
 }
-#[wasm_bindgen]
 
-pub fn value( player_id: usize, depth: i32, alpha: f64, beta: f64) -> JsValue{
-   
-    let mut score = evaluate_C4(player_id);
-    if depth>=4{
-        let mut value = 0.0; 
+//This function evaluates the board for the given player, who spells the word set by set_toot_player
+//Returns the Evaluation, its win is 4 if the player's word is on the board
+#[wasm_bindgen(unchecked_return_type = "Evaluation")]
+#[allow(non_snake_case)]
+pub fn evaluate_TO(player_id: usize) -> JsValue{
+    let playing = PLAYING.read().unwrap();
+    let board = playing.board();
+    let rules = toot_otto();
+    let word = rules.word(player_id);
+
+    let win = if evaluate::has_word_TO(board, word) { word.len() } else { 0 };
+    let score = rules.evaluate(board, player_id);
+    to_js(&Evaluation{win, score})
+}
 
-        let mut win = score.get(0).as_f64().unwrap();
-        let mut total_score = score.get(1).as_f64().unwrap();
+#[wasm_bindgen(unchecked_return_type = "Placed")]
+#[allow(non_snake_case)]
+pub fn medium_TO(player_id: usize) -> JsValue{
+    bot_move_TO(player_id, &BotConfig::medium())
+}
 
-        value = total_score;
+#[wasm_bindgen(unchecked_return_type = "Placed")]
+//Added by AB
+#[allow(non_snake_case)]
+pub fn difficult_TO(player_id: usize) -> JsValue{
+    bot_move_TO(player_id, &BotConfig::hard())
+}
 
-       if win == 4.0 && player_id == 2 {
-        value = 999999.0;
-       }
-       else if (win == 4.0 && player_id == 1){
-        value = 999999.0*-1.0;
-       }
-       
-       if depth %2 == 0 {
-        return minState_C4(player_id,depth+1 , alpha, beta);
-       }
-       return maxState_C4(player_id, depth, alpha, beta);
+// Let a bot with the given config move for player_id in the game played by the rules R
+// Returns the (row, col, piece) it inserted, or None if it has no move or the game is not played by R
+fn bot_move<R: Rules + Clone + 'static>(player_id: usize, config: &BotConfig) -> Option<(usize, usize, R::Piece)> {
+    with_game(|game: &mut PlayedGame<R>| {
+        let mut state = GameState{to_move: player_id, ..game.state.clone()};
+        let (col, piece) = bot::choose_move(&mut state, config, &mut *RNG.write().unwrap())?;
+        let row = game.play(col, player_id, piece, true).unwrap_or(0);
+        Some((row, col, piece))
+    }).flatten()
+}
 
-    }
+#[wasm_bindgen(unchecked_return_type = "Placed")]
+// Let a bot with the given config play a letter for player_id, who spells the word set by set_toot_player
+// Returns the inserted piece, its row is 0 if the board is full
+#[allow(non_snake_case)]
+pub fn bot_move_TO(player_id: usize, config: &BotConfig) -> JsValue{
+    let (row, col, letter) = bot_move::<TootOtto>(player_id, config).unwrap_or((0, 0, 'T'));
+    to_js(&Placed{row, col, piece: js::Piece::Letter(letter)})
+}
 
-    else{
-        return maxState_C4(player_id, depth, alpha, beta);
-     
-    
-    }
 
+//---------------------------------------------------C4 GAME --------------------------------------
 
+#[wasm_bindgen(unchecked_return_type = "Placed")]
+//Added by AB
+#[allow(non_snake_case)]
+pub fn easy_bot_C4(player_id: usize) -> JsValue{
+    bot_move_C4(player_id, &BotConfig::easy())
 }
 
-#[wasm_bindgen]
-
-pub fn choose_C4(choice: Vec<i32>) -> i32 {
-    let mut index = rand::thread_rng().gen_range(0..choice.len());
-    return choice[index]
+#[wasm_bindgen(unchecked_return_type = "Evaluation")]
+//This function evaluates the board for the given player
+//Returns the Evaluation, its win is the win length if the player has that many in a row
+#[allow(non_snake_case)]
+pub fn evaluate_C4(player_id: usize) -> JsValue{
+    let playing = PLAYING.read().unwrap();
+    let board = playing.board();
+    let has_line = evaluate::windows(board, board.win_len).iter()
+        .any(|window| window.iter().all(|&(row, col)| board.player_at(row, col) == Some(player_id)));
+
+    let win = if has_line { board.win_len } else { 0 };
+    let score = Connect4.evaluate(board, player_id);
+    to_js(&Evaluation{win, score})
 }
 
-#[wasm_bindgen]
-pub fn maxState_C4(player_id: usize, depth: i32, alpha: f64, beta: f64) -> JsValue {
-    let mut val = -100000000007.0;
-    let mut play = -1;
-    let mut tempValue = Array::new();
-    let mut tempState = 0;
-    let mut moves = Array::new();
-
-    let mut new_board = BOARD.write().unwrap();
-
-    for i in (0..7) {
-        // Insert those pieces in the cloned board new_board.set_piece(row, col, player)
-        if tempState > 0 {
-           // tempValue = value(tempState, 2, depth, alpha, beta);
-            let temp = tempValue.get(0).as_f64().unwrap();
-            if temp > val {
-                val = temp;
-                play = i;
-                moves.push(&JsValue::from(i));
-            } else if temp == val {
-                moves.push(&JsValue::from(i));
-            }
-
-            if val > beta {
-                let vec = moves.to_vec();
-                let js_values = vec![JsValue::from(1), JsValue::from(2), JsValue::from(3)];
-                let i32_values: Vec<i32> =
-                    js_values.iter().map(|value| value.as_f64().unwrap() as i32).collect();
-
-                play = choose_C4(i32_values);
-                let result = Array::new();
-                result.push(&JsValue::from(val));
-                result.push(&JsValue::from(play));
-                return result.into();
-            }
-            let mut alpha = 0.0;
-            if val > alpha {
-                alpha = val;
-            }
-        }
-    }
-    let js_values = vec![JsValue::from(1), JsValue::from(2), JsValue::from(3)];
-    let i32_values: Vec<i32> = js_values.iter().map(|value| value.as_f64().unwrap() as i32).collect();
-
-    play = choose_C4(i32_values).into();
-    let result = Array::new();
-    result.push(&JsValue::from(val));
-    result.push(&JsValue::from(play));
-    return result.into();
+#[wasm_bindgen(unchecked_return_type = "Placed")]
+//Added by AB
+#[allow(non_snake_case)]
+pub fn difficult_C4(player_id: usize) -> JsValue{
+    bot_move_C4(player_id, &BotConfig::hard())
 }
 
-#[wasm_bindgen]
-pub fn minState_C4(player_id: usize, depth: i32, alpha: f64, beta: f64) -> JsValue{
-    let mut val = -100000000007.0;
-    let mut play = -1;
-    let mut tempValue = Array::new();
-    let mut tempState = 0.0;
-    let mut moves = Array::new();
-
-    for i in (0..7) {
-        // Insert those pieces in the cloned board new_board.set_piece(row, col, player)
-        if tempState > 0.0 {
-          //  tempValue = value(tempState, 2, depth, alpha, beta);
-            let temp = tempValue.get(0).as_f64().unwrap();
-            if temp < val {
-                val = temp;
-                play = i;
-                moves.push(&JsValue::from(i));
-            } else if temp == val {
-                moves.push(&JsValue::from(i));
-            }
-            
-
-            if val < alpha {
-                let vec = moves.to_vec();
-                let js_values = vec![JsValue::from(1), JsValue::from(2), JsValue::from(3)];
-                let i32_values: Vec<i32> =
-                    js_values.iter().map(|value| value.as_f64().unwrap() as i32).collect();
-
-                play = choose_C4(i32_values);
-                let result = Array::new();
-                result.push(&JsValue::from(val));
-                result.push(&JsValue::from(play));
-                return result.into();
-            }
-            let mut alpha = 0.0;
-            if val > beta {
-                alpha = val;
-            }
-        }
-    }
+#[wasm_bindgen(unchecked_return_type = "Placed")]
+#[allow(non_snake_case)]
+pub fn medium_C4(player_id: usize) -> JsValue{
+    bot_move_C4(player_id, &BotConfig::medium())
+}
 
-    let js_values = vec![JsValue::from(1), JsValue::from(2), JsValue::from(3)];
-    let i32_values: Vec<i32> = js_values.iter().map(|value| value.as_f64().unwrap() as i32).collect();
+#[wasm_bindgen(unchecked_return_type = "Placed")]
+// Let a bot with the given config drop a piece for player_id
+// Returns the inserted piece, its row is 0 if the board is full
+#[allow(non_snake_case)]
+pub fn bot_move_C4(player_id: usize, config: &BotConfig) -> JsValue{
+    let (row, col, _) = bot_move::<Connect4>(player_id, config).unwrap_or((0, 0, player_id));
+    to_js(&Placed{row, col, piece: js::Piece::Player(player_id)})
+}
 
-    play = choose_C4(i32_values).into();
-    let result = Array::new();
-    result.push(&JsValue::from(val));
-    result.push(&JsValue::from(play));
-    return result.into();
-            
 
+//---------------------------------------------------POP OUT GAME --------------------------------------
 
+#[wasm_bindgen(unchecked_return_type = "MovePO")]
+// Let a bot with the given config drop or pop a piece for player_id
+// Returns the move it made, its row is 0 if it had no move
+#[allow(non_snake_case)]
+pub fn bot_move_PO(player_id: usize, config: &BotConfig) -> JsValue{
+    let played = match bot_move::<PopOut>(player_id, config) {
+        Some((row, col, piece)) => MovePO{row, col, pop: piece == PopOutMove::Pop},
+        None => MovePO{row: 0, col: 0, pop: false},
+    };
+    to_js(&played)
+}
 
+#[wasm_bindgen(unchecked_return_type = "MovePO")]
+#[allow(non_snake_case)]
+pub fn easy_bot_PO(player_id: usize) -> JsValue{
+    bot_move_PO(player_id, &BotConfig::easy())
 }
 
-#[wasm_bindgen]
+#[wasm_bindgen(unchecked_return_type = "MovePO")]
+#[allow(non_snake_case)]
+pub fn medium_PO(player_id: usize) -> JsValue{
+    bot_move_PO(player_id, &BotConfig::medium())
+}
 
-pub fn medium_C4(player_id: usize) -> Array{
-    let mut column = 0;
-    let mut  row = 0;
-    let mut data = 0;
-  
-   // let board = BOARD.read().unwrap();
-
-    loop{
-        let mut rng = rand::thread_rng();
-        let column = rng.gen_range(1..7);
-        
-        let insert_obj = insert_piece_C4(column, player_id);
-       
-
-         if insert_obj>0 && insert_obj>0 {
-            let arr = Array::new();
-    
-        arr.push(&JsValue::from(insert_obj));
-        arr.push(&JsValue::from(column));
-        return arr;
-         }
+#[wasm_bindgen(unchecked_return_type = "MovePO")]
+#[allow(non_snake_case)]
+pub fn difficult_PO(player_id: usize) -> JsValue{
+    bot_move_PO(player_id, &BotConfig::hard())
+}
 
-        let win = check_for_win_C4(insert_obj, column, player_id);
-        if win == true {
-            alert(format!("Player 2 has won!!!", ).as_str());
 
-        }
+//---------------------------------------------------POWER-UP GAME --------------------------------------
 
-    }
-    
-    
+#[wasm_bindgen(unchecked_return_type = "MovePU")]
+// Let a bot with the given config play a disc or power piece for player_id
+// Returns the move it made, its row is 0 if it had no move
+#[allow(non_snake_case)]
+pub fn bot_move_PU(player_id: usize, config: &BotConfig) -> JsValue{
+    let played = match bot_move::<PowerUp>(player_id, config) {
+        Some((row, col, power)) => MovePU{row, col, power},
+        None => MovePU{row: 0, col: 0, power: Power::Disc},
+    };
+    to_js(&played)
 }
 
+#[wasm_bindgen(unchecked_return_type = "MovePU")]
+#[allow(non_snake_case)]
+pub fn easy_bot_PU(player_id: usize) -> JsValue{
+    bot_move_PU(player_id, &BotConfig::easy())
+}
 
+#[wasm_bindgen(unchecked_return_type = "MovePU")]
+#[allow(non_snake_case)]
+pub fn medium_PU(player_id: usize) -> JsValue{
+    bot_move_PU(player_id, &BotConfig::medium())
+}
 
-
-
-  
+#[wasm_bindgen(unchecked_return_type = "MovePU")]
+#[allow(non_snake_case)]
+pub fn difficult_PU(player_id: usize) -> JsValue{
+    bot_move_PU(player_id, &BotConfig::hard())
+}
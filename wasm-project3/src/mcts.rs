@@ -0,0 +1,140 @@
+use rand::Rng;
+
+use crate::bot::{self, BotConfig, Deadline, Position, Status};
+
+// Exploration constant of the UCT formula
+const EXPLORATION: f64 = 1.41;
+// Iterations to run when the config sets neither an iteration count nor a time budget
+const DEFAULT_ITERATIONS: u32 = 2000;
+// Playouts longer than this are scored as a draw, Pop Out games can go on forever
+const MAX_PLAYOUT_MOVES: usize = 100;
+
+// A node of the search tree, reached by playing `mv` from its parent
+struct Node<M> {
+    mv: Option<M>,
+    parent: Option<usize>,
+    children: Vec<usize>,
+    untried: Vec<M>,
+    // how the game ended if `mv` finished it
+    status: Status,
+    visits: u32,
+    // total reward for the player who played `mv`, 1 per win and 0.5 per draw
+    reward: f64,
+}
+
+impl<M> Node<M> {
+    fn new(mv: Option<M>, parent: Option<usize>, untried: Vec<M>, status: Status) -> Node<M> {
+        Node{mv, parent, children: vec![], untried, status, visits: 0, reward: 0.0}
+    }
+
+    // upper confidence bound of the node, seen from the player choosing between its siblings
+    fn uct(&self, parent_visits: u32) -> f64 {
+        let visits = self.visits as f64;
+        self.reward / visits + EXPLORATION * ((parent_visits as f64).ln() / visits).sqrt()
+    }
+}
+
+// Reward for the player who made a move that ended the game with `status`
+fn status_reward(status: Status) -> f64 {
+    match status {
+        Status::Won => 1.0,
+        Status::Draw => 0.5,
+        _ => 0.0,
+    }
+}
+
+// Play random moves from the position until the game ends, then take them back. With
+// heuristic playouts the moves are picked among the tactical ones, so wins are taken and
+// obvious losses avoided. Returns the reward for the player who moved last before the playout.
+fn playout<P: Position, R: Rng>(pos: &mut P, heuristic: bool, rng: &mut R) -> f64 {
+    let mut played = vec![];
+    let mut reward = 0.5;
+    loop {
+        let moves = if heuristic { bot::tactical_moves(pos) } else { pos.legal_moves() };
+        if moves.is_empty() || played.len() == MAX_PLAYOUT_MOVES {
+            break;
+        }
+        let mv = moves[rng.gen_range(0..moves.len())];
+        let status = pos.play(mv);
+        played.push(mv);
+        if status != Status::Ongoing {
+            // moves alternate, so an odd number of playout moves were made by the opponent
+            let mover_reward = status_reward(status);
+            reward = if played.len() % 2 == 1 { 1.0 - mover_reward } else { mover_reward };
+            break;
+        }
+    }
+    for &mv in played.iter().rev() {
+        pos.undo(mv);
+    }
+    reward
+}
+
+// Monte Carlo tree search with UCT over the given root moves. Runs `config.iterations`
+// iterations, or as many as fit in `config.time_budget_ms` if no count is set, and returns
+// each root move with the number of times it was visited.
+pub fn search<P: Position, R: Rng>(pos: &mut P, moves: &[P::Move], config: &BotConfig, rng: &mut R) -> Vec<(P::Move, u32)> {
+    let deadline = Deadline::new(config.time_budget_ms, 0);
+    let iterations = match config.iterations {
+        0 if config.time_budget_ms > 0.0 => u32::MAX,
+        0 => DEFAULT_ITERATIONS,
+        iterations => iterations,
+    };
+    let mut tree = vec![Node::new(None, None, moves.to_vec(), Status::Ongoing)];
+
+    for _ in 0..iterations {
+        if deadline.expired_now() {
+            break;
+        }
+        // selection: follow the best children down to a node that can still grow
+        let mut node = 0;
+        let mut path = vec![];
+        while tree[node].untried.is_empty() && !tree[node].children.is_empty() {
+            let parent_visits = tree[node].visits;
+            node = *tree[node].children.iter()
+                .max_by(|&&a, &&b| tree[a].uct(parent_visits).total_cmp(&tree[b].uct(parent_visits)))
+                .unwrap();
+            let mv = tree[node].mv.unwrap();
+            pos.play(mv);
+            path.push(mv);
+        }
+
+        // expansion: add one untried move if the game is not over here
+        if tree[node].status == Status::Ongoing && !tree[node].untried.is_empty() {
+            let pick = rng.gen_range(0..tree[node].untried.len());
+            let mv = tree[node].untried.swap_remove(pick);
+            let status = pos.play(mv);
+            path.push(mv);
+            let untried = if status == Status::Ongoing { pos.legal_moves() } else { vec![] };
+            tree.push(Node::new(Some(mv), Some(node), untried, status));
+            let child = tree.len() - 1;
+            tree[node].children.push(child);
+            node = child;
+        }
+
+        // simulation: finished games score themselves, otherwise play the game out
+        let mut reward = match tree[node].status {
+            Status::Ongoing => playout(pos, config.heuristic_playouts, rng),
+            status => status_reward(status),
+        };
+
+        // backpropagation: the reward flips between the players on the way up
+        let mut current = Some(node);
+        while let Some(index) = current {
+            tree[index].visits += 1;
+            tree[index].reward += reward;
+            reward = 1.0 - reward;
+            current = tree[index].parent;
+        }
+        for &mv in path.iter().rev() {
+            pos.undo(mv);
+        }
+    }
+
+    let mut visited: Vec<(P::Move, u32)> = tree[0].children.iter()
+        .map(|&child| (tree[child].mv.unwrap(), tree[child].visits))
+        .collect();
+    // moves the search never got to still count as options
+    visited.extend(tree[0].untried.iter().map(|&mv| (mv, 0)));
+    visited
+}
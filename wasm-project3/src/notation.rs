@@ -0,0 +1,461 @@
+use crate::bot::{Position, Status};
+use crate::rules::{Connect4, GameMode, GameState, PopOut, Rules, TootOtto};
+use crate::Board;
+use serde::{Deserialize, Serialize};
+use std::any::Any;
+use tsify::Tsify;
+
+// Most rows or columns a board read from a position string may have
+pub const MAX_BOARD_SIDE: usize = 20;
+
+// Play the moves of a game from the start, checking each one is legal on the board.
+// Returns the game after the last move, or an error naming the first move (counting from 1)
+// that could not be played.
+pub fn replay<R: Rules>(rules: R, board: Board, moves: &[(usize, R::Piece)]) -> Result<GameState<R>, String> {
+    let mut state = GameState::new(rules, board);
+    let mut finished = false;
+    for (i, &(col, piece)) in moves.iter().enumerate() {
+        if finished {
+            return Err(format!("move {} is played after the game ended", i + 1));
+        }
+        if col < 1 || col > state.board.num_cols() {
+            return Err(format!("move {} is in column {}, the board has {} columns", i + 1, col, state.board.num_cols()));
+        }
+        if state.board.is_col_full(col) && !state.legal_moves().contains(&(col, piece)) {
+            return Err(format!("move {} is in column {}, which is full", i + 1, col));
+        }
+        if !state.legal_moves().contains(&(col, piece)) {
+            return Err(format!("move {} drops {:?}, which player {} cannot play", i + 1, piece, state.to_move));
+        }
+        finished = state.play((col, piece)) != Status::Ongoing;
+    }
+    Ok(state)
+}
+
+// Parse the columns of a Connect4 game in column-sequence notation, e.g. "4453323" where
+// each digit is the column (1 to 9) a piece was dropped in, player 1 moving first.
+// Spaces are allowed between moves.
+#[allow(non_snake_case)]
+pub fn parse_columns_C4(notation: &str) -> Result<Vec<usize>, String> {
+    notation.chars()
+        .filter(|c| !c.is_whitespace())
+        .enumerate()
+        .map(|(i, c)| match c.to_digit(10) {
+            Some(col) if col >= 1 => Ok(col as usize),
+            _ => Err(format!("move {} is '{}', expected a column from 1 to 9", i + 1, c)),
+        })
+        .collect()
+}
+
+// Parse the moves of a Connect4 game in column-sequence notation
+#[allow(non_snake_case)]
+pub fn moves_C4(notation: &str) -> Result<Vec<(usize, usize)>, String> {
+    let cols = parse_columns_C4(notation)?;
+    // players alternate, so the piece is the id of the player whose turn it is
+    Ok(cols.into_iter().enumerate().map(|(i, col)| (col, 1 + i % 2)).collect())
+}
+
+// Parse a Connect4 game in column-sequence notation and play it out on the given empty board
+#[allow(non_snake_case)]
+pub fn parse_C4(notation: &str, board: Board) -> Result<GameState<Connect4>, String> {
+    replay(Connect4, board, &moves_C4(notation)?)
+}
+
+// Error for a mode whose games have no notation to be read from, only Connect4 and Toot-Otto do
+pub fn no_notation(mode: GameMode) -> String {
+    format!("{} games have no notation, only Connect4 and Toot-Otto games do", mode.name())
+}
+
+// Write the columns of a Connect4 game in column-sequence notation
+#[allow(non_snake_case)]
+pub fn write_C4(cols: &[usize]) -> Result<String, String> {
+    cols.iter()
+        .map(|&col| match col {
+            1..=9 => Ok(char::from(b'0' + col as u8)),
+            _ => Err(format!("column {} cannot be written as a single digit", col)),
+        })
+        .collect()
+}
+
+// How a recorded game ended
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Tsify)]
+pub enum RecordResult {
+    // the player with this id won, written "1-0" or "0-1"
+    Win(usize),
+    // a tie or a full board, written "1/2-1/2"
+    Draw,
+    // the game was not finished, written "*"
+    Unfinished,
+}
+
+impl RecordResult {
+    fn write(self) -> &'static str {
+        match self {
+            RecordResult::Win(1) => "1-0",
+            RecordResult::Win(_) => "0-1",
+            RecordResult::Draw => "1/2-1/2",
+            RecordResult::Unfinished => "*",
+        }
+    }
+
+    fn parse(text: &str) -> Result<RecordResult, String> {
+        match text {
+            "1-0" => Ok(RecordResult::Win(1)),
+            "0-1" => Ok(RecordResult::Win(2)),
+            "1/2-1/2" => Ok(RecordResult::Draw),
+            "*" => Ok(RecordResult::Unfinished),
+            _ => Err(format!("unknown result '{}', expected 1-0, 0-1, 1/2-1/2 or *", text)),
+        }
+    }
+}
+
+// A Toot-Otto game as written in notation: header lines like [Player1 "alice"] followed by
+// the moves, each a column and a letter, e.g.
+//
+//     [Player1 "alice"]
+//     [Player2 "Hard Bot"]
+//     [Toot "1"]
+//     [Result "0-1"]
+//     3T 4O 3O 2T
+//
+// Toot is the id of the player spelling TOOT, the other player spells OTTO. In header values
+// a backslash, quote, closing bracket or line break is written with a backslash before it,
+// e.g. [Player1 "the \"bot\""], with \n for the line break.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordTO {
+    pub players: [String; 2],
+    pub toot_player: usize,
+    pub result: RecordResult,
+    pub moves: Vec<(usize, char)>,
+}
+
+impl RecordTO {
+    // the rules the game was played by
+    pub fn rules(&self) -> TootOtto {
+        TootOtto::new(self.toot_player)
+    }
+
+    // play the recorded moves out on the given empty board, checking the result if they end the game
+    pub fn game(&self, board: Board) -> Result<GameState<TootOtto>, String> {
+        let game = replay(self.rules(), board, &self.moves)?;
+        let result = result_of(&game, self.moves.last());
+        if result != RecordResult::Unfinished && self.result != result {
+            return Err(format!("the moves end the game {}, but the result is {}", result.write(), self.result.write()));
+        }
+        Ok(game)
+    }
+}
+
+// How the game ended with the given last move, Unfinished if it is still going
+pub fn result_of<R: Rules>(game: &GameState<R>, last: Option<&(usize, R::Piece)>) -> RecordResult {
+    let col = match last {
+        Some(&(col, _)) => col,
+        None => return RecordResult::Unfinished,
+    };
+    // the last piece is the top one of its column
+    let row = game.board.drop_row(col).map_or(1, |row| row + 1);
+    // the player who made the last move is the one not to move now
+    match game.rules.outcome(&game.board, row, col, 3 - game.to_move) {
+        None => RecordResult::Unfinished,
+        Some(outcome) => match game.rules.winner(outcome) {
+            Some(player_id) => RecordResult::Win(player_id),
+            None => RecordResult::Draw,
+        },
+    }
+}
+
+// Write a header value with its backslashes, quotes, closing brackets and line breaks escaped
+fn escape_header(value: &str) -> String {
+    let mut escaped = String::new();
+    for c in value.chars() {
+        match c {
+            '\\' | '"' | ']' => {
+                escaped.push('\\');
+                escaped.push(c);
+            },
+            '\n' => escaped.push_str("\\n"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+// Read a header value written by escape_header
+fn unescape_header(value: &str) -> Result<String, String> {
+    let mut unescaped = String::new();
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some(c @ ('\\' | '"' | ']')) => unescaped.push(c),
+            Some('n') => unescaped.push('\n'),
+            Some(c) => return Err(format!("unknown escape '\\{}' in header value '{}'", c, value)),
+            None => return Err(format!("header value '{}' ends with a lone backslash", value)),
+        }
+    }
+    Ok(unescaped)
+}
+
+// Parse a Toot-Otto game in notation, a missing header takes its default value
+// (players "Player 1" and "Player 2", Toot "1" and Result "*")
+#[allow(non_snake_case)]
+pub fn parse_TO(notation: &str) -> Result<RecordTO, String> {
+    let mut record = RecordTO{
+        players: ["Player 1".to_string(), "Player 2".to_string()],
+        toot_player: 1,
+        result: RecordResult::Unfinished,
+        moves: vec![],
+    };
+    for line in notation.lines().map(str::trim).filter(|line| !line.is_empty()) {
+        if let Some(header) = line.strip_prefix('[') {
+            let (key, value) = header.strip_suffix(']')
+                .and_then(|header| header.split_once(' '))
+                .and_then(|(key, value)| Some((key, value.trim().strip_prefix('"')?.strip_suffix('"')?)))
+                .ok_or(format!("expected a header like [Key \"value\"], got '{}'", line))?;
+            match key {
+                "Player1" => record.players[0] = unescape_header(value)?,
+                "Player2" => record.players[1] = unescape_header(value)?,
+                "Toot" => record.toot_player = match value {
+                    "1" => 1,
+                    "2" => 2,
+                    _ => return Err(format!("Toot must be player 1 or 2, got '{}'", value)),
+                },
+                "Result" => record.result = RecordResult::parse(value)?,
+                _ => return Err(format!("unknown header '{}'", key)),
+            }
+            continue;
+        }
+        for token in line.split_whitespace() {
+            let i = record.moves.len() + 1;
+            // the letter is the last character, the column the digits before it
+            let mut chars = token.chars();
+            let letter = chars.next_back().map(|letter| letter.to_ascii_uppercase());
+            match (chars.as_str().parse::<usize>(), letter) {
+                (Ok(col), Some(letter @ ('T' | 'O'))) => record.moves.push((col, letter)),
+                _ => return Err(format!("move {} is '{}', expected a column and a letter like 3T", i, token)),
+            }
+        }
+    }
+    Ok(record)
+}
+
+// Write a Toot-Otto game in notation, the headers first and then the moves on one line
+#[allow(non_snake_case)]
+pub fn write_TO(record: &RecordTO) -> String {
+    let mut text = String::new();
+    text.push_str(&format!("[Player1 \"{}\"]\n", escape_header(&record.players[0])));
+    text.push_str(&format!("[Player2 \"{}\"]\n", escape_header(&record.players[1])));
+    text.push_str(&format!("[Toot \"{}\"]\n", record.toot_player));
+    text.push_str(&format!("[Result \"{}\"]\n", record.result.write()));
+    let moves: Vec<String> = record.moves.iter().map(|(col, letter)| format!("{}{}", col, letter)).collect();
+    text.push_str(&moves.join(" "));
+    text
+}
+
+// A game of either mode, as loaded from a position string
+#[derive(Debug, Clone)]
+pub enum Game {
+    Connect4(GameState<Connect4>),
+    TootOtto(GameState<TootOtto>),
+    PopOut(GameState<PopOut>),
+}
+
+impl Game {
+    pub fn board(&self) -> &Board {
+        match self {
+            Game::Connect4(game) => &game.board,
+            Game::TootOtto(game) => &game.board,
+            Game::PopOut(game) => &game.board,
+        }
+    }
+
+    pub fn to_move(&self) -> usize {
+        match self {
+            Game::Connect4(game) => game.to_move,
+            Game::TootOtto(game) => game.to_move,
+            Game::PopOut(game) => game.to_move,
+        }
+    }
+
+    // the position of a game played by rules of one of the modes above, None for other rules
+    pub fn of<R: Rules + Clone + 'static>(game: &GameState<R>) -> Option<Game> {
+        let game: &dyn Any = game;
+        if let Some(game) = game.downcast_ref::<GameState<Connect4>>() {
+            Some(Game::Connect4(game.clone()))
+        } else if let Some(game) = game.downcast_ref::<GameState<TootOtto>>() {
+            Some(Game::TootOtto(game.clone()))
+        } else {
+            game.downcast_ref::<GameState<PopOut>>().map(|game| Game::PopOut(game.clone()))
+        }
+    }
+}
+
+impl From<GameState<Connect4>> for Game {
+    fn from(game: GameState<Connect4>) -> Game {
+        Game::Connect4(game)
+    }
+}
+
+impl From<GameState<TootOtto>> for Game {
+    fn from(game: GameState<TootOtto>) -> Game {
+        Game::TootOtto(game)
+    }
+}
+
+impl From<GameState<PopOut>> for Game {
+    fn from(game: GameState<PopOut>) -> Game {
+        Game::PopOut(game)
+    }
+}
+
+// Write a position as a string of space separated fields, like FEN for chess:
+//
+//     connect4 6x7 4 -------/-------/-------/-------/---2---/--112-- 1
+//     toot-otto 4x6 4 ------/------/------/-TotO- 1 5T5O,5T5O 1
+//
+// The fields are the game, the board size as rows x cols (with a 'c' after it for a board whose
+// left and right edges join, like a cylinder), the win length, the rows from top to
+// bottom separated by '/', and the id of the player to move. A Connect4 space is '-' when empty
+// or the id of the player whose piece it is. A Toot-Otto space is the letter, upper case for
+// player 1's letters and lower case for player 2's. Toot-Otto adds the letters each player has
+// left and the id of the player spelling TOOT. Pop Out is written like Connect4.
+pub fn to_position_string(game: &Game) -> String {
+    let board = game.board();
+    let mut rows = vec![];
+    for row in 1..=board.num_rows() {
+        let spaces: String = (1..=board.num_cols()).map(|col| match game {
+            Game::Connect4(_) | Game::PopOut(_) => board.player_at(row, col).map_or('-', |id| char::from(b'0' + id as u8)),
+            Game::TootOtto(_) => match (board.letter_at(row, col), board.player_at(row, col)) {
+                (Some(letter), Some(2)) => letter.to_ascii_lowercase(),
+                (Some(letter), _) => letter,
+                (None, _) => '-',
+            },
+        }).collect();
+        rows.push(spaces);
+    }
+    let mut fields = vec![
+        game_name(game).to_string(),
+        format!("{}x{}{}", board.num_rows(), board.num_cols(), if board.wraps() { "c" } else { "" }),
+        board.win_len().to_string(),
+        rows.join("/"),
+        game.to_move().to_string(),
+    ];
+    if let Game::TootOtto(state) = game {
+        fields.push(letters_field(&state.rules, board));
+        fields.push(state.rules.toot_player().to_string());
+    }
+    fields.join(" ")
+}
+
+// the letters each player has left, e.g. "5T6O,5T5O" for players 1 and 2
+fn letters_field(rules: &TootOtto, board: &Board) -> String {
+    let letters: Vec<String> = [1, 2].iter().map(|&player_id| {
+        let (t_left, o_left) = rules.letters_left(board, player_id);
+        format!("{}T{}O", t_left, o_left)
+    }).collect();
+    letters.join(",")
+}
+
+// name of the game as the first field of its position string
+pub fn game_name(game: &Game) -> &'static str {
+    match game {
+        Game::Connect4(_) => "connect4",
+        Game::TootOtto(_) => "toot-otto",
+        Game::PopOut(_) => "popout",
+    }
+}
+
+// Read a position written by to_position_string. The position must be one that can come up
+// in a game: no piece floating above an empty space, the players' piece counts matching
+// the player to move, and for Toot-Otto the letters left matching the ones on the board.
+// Pops leave any piece counts, so a Pop Out position may have either player to move.
+pub fn from_position_string(position: &str) -> Result<Game, String> {
+    let fields: Vec<&str> = position.split_whitespace().collect();
+    let game = match fields.first() {
+        Some(&name @ ("connect4" | "toot-otto" | "popout")) => name,
+        _ => return Err("the position must start with the game, connect4, toot-otto or popout".to_string()),
+    };
+    let toot_otto = game == "toot-otto";
+    let num_fields = if toot_otto { 7 } else { 5 };
+    if fields.len() != num_fields {
+        return Err(format!("expected {} fields, got {}", num_fields, fields.len()));
+    }
+
+    let (size, wraps) = match fields[1].strip_suffix('c') {
+        Some(size) => (size, true),
+        None => (fields[1], false),
+    };
+    let (num_rows, num_cols) = size.split_once('x')
+        .and_then(|(rows, cols)| Some((rows.parse::<usize>().ok()?, cols.parse::<usize>().ok()?)))
+        .filter(|&(rows, cols)| rows > 0 && cols > 0)
+        .ok_or(format!("board size '{}' is not like 6x7", fields[1]))?;
+    if num_rows > MAX_BOARD_SIDE || num_cols > MAX_BOARD_SIDE {
+        return Err(format!("board size '{}' is too big, boards have at most {} rows and columns", fields[1], MAX_BOARD_SIDE));
+    }
+    let win_len = fields[2].parse::<usize>().ok()
+        .filter(|&win_len| win_len >= 2)
+        .ok_or(format!("win length '{}' is not a number of 2 or more", fields[2]))?;
+    if win_len > num_rows.max(num_cols) {
+        return Err(format!("win length {} does not fit on a {}x{} board", win_len, num_rows, num_cols));
+    }
+    let rows: Vec<&str> = fields[3].split('/').collect();
+    if rows.len() != num_rows {
+        return Err(format!("expected {} rows, got {}", num_rows, rows.len()));
+    }
+    let mut board = Board::with_size(num_rows, num_cols, win_len);
+    board.set_wraps(wraps);
+    // fill the columns from the bottom up so the pieces land where the string says
+    for (i, spaces) in rows.iter().enumerate().rev() {
+        let row = i + 1;
+        if spaces.chars().count() != num_cols {
+            return Err(format!("row {} has {} spaces, expected {}", row, spaces.chars().count(), num_cols));
+        }
+        for (col, space) in (1..=num_cols).zip(spaces.chars()) {
+            if space == '-' {
+                continue;
+            }
+            if board.drop_row(col) != Some(row) {
+                return Err(format!("the piece at R{}C{} is floating above an empty space", row, col));
+            }
+            let dropped = match (toot_otto, space) {
+                (false, '1') => Connect4.drop_piece(&mut board, col, 1, 1),
+                (false, '2') => Connect4.drop_piece(&mut board, col, 2, 2),
+                (true, 'T') | (true, 'O') => TootOtto::default().drop_piece(&mut board, col, 1, space),
+                (true, 't') | (true, 'o') => TootOtto::default().drop_piece(&mut board, col, 2, space.to_ascii_uppercase()),
+                _ => None,
+            };
+            if dropped.is_none() {
+                return Err(format!("unknown space '{}' at R{}C{}", space, row, col));
+            }
+        }
+    }
+
+    let to_move = match fields[4] {
+        "1" => 1,
+        "2" => 2,
+        _ => return Err(format!("the player to move must be 1 or 2, got '{}'", fields[4])),
+    };
+    if game != "popout" && board.player_to_move() != to_move {
+        return Err(format!("player {} is to move with {} and {} pieces on the board, not player {}",
+            board.player_to_move(), board.count_pieces(1), board.count_pieces(2), to_move));
+    }
+
+    match game {
+        "connect4" => return Ok(Game::Connect4(GameState{rules: Connect4, board, to_move})),
+        "popout" => return Ok(Game::PopOut(GameState{rules: PopOut, board, to_move})),
+        _ => {},
+    }
+    let rules = match fields[6] {
+        "1" => TootOtto::new(1),
+        "2" => TootOtto::new(2),
+        _ => return Err(format!("TOOT must be spelled by player 1 or 2, got '{}'", fields[6])),
+    };
+    let letters = letters_field(&rules, &board);
+    if fields[5] != letters {
+        return Err(format!("the letters left are {} for the pieces on the board, not {}", letters, fields[5]));
+    }
+    Ok(Game::TootOtto(GameState{rules, board, to_move}))
+}
@@ -0,0 +1,295 @@
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+use tsify::Tsify;
+use wasm_bindgen::prelude::*;
+
+use crate::bot::{self, BotConfig, Position, Status};
+use crate::js::{self, Placed};
+use crate::notation::{self, Game};
+use crate::rules::{Connect4, GameMode, GameState, Rules, TootOtto};
+use crate::Board;
+
+// Longest forced win looked for, in moves of the player solving the puzzle
+pub const MAX_WIN_IN: usize = 3;
+
+// Most bot games one request may have played to look for puzzles in
+pub const MAX_SELF_PLAY_GAMES: usize = 50;
+
+// A position where the player to move can force a win in win_in of their moves, and only
+// one first move does it. The solution is the winning line with the longest defence.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Tsify)]
+pub struct Puzzle {
+    // "connect4" or "toot-otto"
+    pub game: String,
+    // see notation::to_position_string
+    pub position: String,
+    pub win_in: usize,
+    pub solution: Vec<Placed>,
+}
+
+// Return true if playing mv wins within n moves of the side to move, whatever the opponent replies
+fn forces_win<P: Position>(pos: &mut P, mv: P::Move, n: usize) -> bool {
+    let wins = match pos.play(mv) {
+        Status::Won => true,
+        Status::Ongoing if n > 1 => {
+            let replies = pos.legal_moves();
+            !replies.is_empty() && replies.into_iter().all(|reply| {
+                let wins = match pos.play(reply) {
+                    // the opponent's reply lost the game for them
+                    Status::Lost => true,
+                    Status::Ongoing => !winning_moves(pos, n - 1).is_empty(),
+                    Status::Won | Status::Draw => false,
+                };
+                pos.undo(reply);
+                wins
+            })
+        },
+        _ => false,
+    };
+    pos.undo(mv);
+    wins
+}
+
+// Every move of the side to move that wins within n of their moves, in legal move order
+pub fn winning_moves<P: Position>(pos: &mut P, n: usize) -> Vec<P::Move> {
+    pos.legal_moves().into_iter().filter(|&mv| forces_win(pos, mv, n)).collect()
+}
+
+// Fewest moves the side to move needs to force a win, None if they cannot within max_n
+pub fn win_in<P: Position>(pos: &mut P, max_n: usize) -> Option<usize> {
+    (1..=max_n).find(|&n| !winning_moves(pos, n).is_empty())
+}
+
+// The opponent's reply that holds out longest against a win forced within n moves, leaving
+// the fewest winning moves among those, the first such reply in legal move order.
+// None if the game is over
+pub fn longest_defence<P: Position>(pos: &mut P, n: usize) -> Option<P::Move> {
+    let mut best: Option<(P::Move, (usize, isize))> = None;
+    for reply in pos.legal_moves() {
+        let holds_out = match pos.play(reply) {
+            Status::Ongoing => match win_in(pos, n) {
+                Some(win_in) => (win_in, -(winning_moves(pos, win_in).len() as isize)),
+                None => (n + 1, 0),
+            },
+            _ => (0, 0),
+        };
+        pos.undo(reply);
+        if best.is_none_or(|(_, longest)| holds_out > longest) {
+            best = Some((reply, holds_out));
+        }
+    }
+    best.map(|(reply, _)| reply)
+}
+
+// the piece the last drop into col landed as
+fn placed_in(board: &Board, col: usize) -> Placed {
+    let row = board.drop_row(col).map_or(1, |row| row + 1);
+    Placed::from_space(&board.get_piece(row, col)).expect("a piece was just dropped")
+}
+
+// The puzzle in the position, if the player to move has a forced win in 2 to max_n moves
+// with a single first move that wins
+pub fn puzzle_at<R>(state: &GameState<R>, max_n: usize) -> Option<Puzzle>
+where R: Rules + Clone, GameState<R>: Into<Game> {
+    let mut pos = state.clone();
+    let n = win_in(&mut pos, max_n)?;
+    if n < 2 || winning_moves(&mut pos, n).len() != 1 {
+        return None;
+    }
+    let game = state.clone().into();
+    let mut solution = vec![];
+    for moves_left in (1..=n).rev() {
+        let mv = winning_moves(&mut pos, moves_left)[0];
+        let status = pos.play(mv);
+        solution.push(placed_in(&pos.board, mv.0));
+        if status != Status::Ongoing {
+            break;
+        }
+        let reply = longest_defence(&mut pos, moves_left - 1)?;
+        let status = pos.play(reply);
+        solution.push(placed_in(&pos.board, reply.0));
+        if status != Status::Ongoing {
+            break;
+        }
+    }
+    Some(Puzzle{
+        game: notation::game_name(&game).to_string(),
+        position: notation::to_position_string(&game),
+        win_in: n,
+        solution,
+    })
+}
+
+// Every puzzle that came up in a game played from the given empty board
+pub fn puzzles_in_game<R>(rules: R, board: Board, moves: &[(usize, R::Piece)], max_n: usize) -> Result<Vec<Puzzle>, String>
+where R: Rules + Clone, GameState<R>: Into<Game> {
+    notation::replay(rules.clone(), board.clone(), moves)?;
+    let mut state = GameState::new(rules, board);
+    let mut puzzles = vec![];
+    for &mv in moves {
+        puzzles.extend(puzzle_at(&state, max_n));
+        state.play(mv);
+    }
+    Ok(puzzles)
+}
+
+// Every puzzle that came up in a game written in notation, column-sequence notation for
+// Connect4 and Toot-Otto notation for Toot-Otto, on the standard board of the game
+pub fn puzzles_in_notation(mode: GameMode, notation: &str, max_n: usize) -> Result<Vec<Puzzle>, String> {
+    match mode {
+        GameMode::Connect4 => puzzles_in_game(Connect4, Board::standard(mode), &notation::moves_C4(notation)?, max_n),
+        GameMode::TootOtto => {
+            let record = notation::parse_TO(notation)?;
+            let board = Board::standard(mode);
+            record.game(board.clone())?;
+            puzzles_in_game(record.rules(), board, &record.moves, max_n)
+        },
+        _ => Err(notation::no_notation(mode)),
+    }
+}
+
+// Play a game between two bots with the given config from the given empty board, returns its moves
+pub fn self_play<R: Rules + Clone, G: Rng>(rules: R, board: Board, config: &BotConfig, rng: &mut G) -> Vec<(usize, R::Piece)> {
+    let mut state = GameState::new(rules, board);
+    let mut moves = vec![];
+    while let Some(mv) = bot::choose_move(&mut state, config, rng) {
+        moves.push(mv);
+        if state.play(mv) != Status::Ongoing {
+            break;
+        }
+    }
+    moves
+}
+
+// Puzzles from the given number of bot games of Connect4 or Toot-Otto, the same seed
+// gives the same puzzles. A position that comes up in several games gives one puzzle
+pub fn generate(mode: GameMode, num_games: usize, seed: u64, max_n: usize) -> Result<Vec<Puzzle>, String> {
+    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+    // loose enough that the games differ and walk into forced wins
+    let config = BotConfig{node_budget: 0, ..BotConfig::medium()};
+    let mut puzzles: Vec<Puzzle> = vec![];
+    for _ in 0..num_games {
+        let board = Board::standard(mode);
+        let found = match mode {
+            GameMode::Connect4 => {
+                let moves = self_play(Connect4, board.clone(), &config, &mut rng);
+                puzzles_in_game(Connect4, board, &moves, max_n)?
+            },
+            GameMode::TootOtto => {
+                let moves = self_play(TootOtto::default(), board.clone(), &config, &mut rng);
+                puzzles_in_game(TootOtto::default(), board, &moves, max_n)?
+            },
+            _ => return Err(format!("{} has no puzzles, only Connect4 and Toot-Otto do", mode.name())),
+        };
+        for puzzle in found {
+            if !puzzles.iter().any(|known| known.position == puzzle.position) {
+                puzzles.push(puzzle);
+            }
+        }
+    }
+    Ok(puzzles)
+}
+
+// What happened to a move tried in a puzzle
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Tsify)]
+pub struct PuzzleStep {
+    // the move still forces the win, a wrong move is not played
+    pub correct: bool,
+    pub solved: bool,
+    // the opponent's forced reply, played after a correct move that did not win yet
+    #[tsify(optional)]
+    pub reply: Option<Placed>,
+    pub moves_left: usize,
+}
+
+// Try a move of the solver, playing the longest defence after it if it keeps the forced win
+fn try_move<R: Rules + Clone>(state: &mut GameState<R>, moves_left: &mut usize, mv: (usize, R::Piece)) -> PuzzleStep {
+    let correct = *moves_left > 0 && state.legal_moves().contains(&mv) && forces_win(state, mv, *moves_left);
+    if !correct {
+        return PuzzleStep{correct, solved: false, reply: None, moves_left: *moves_left};
+    }
+    *moves_left -= 1;
+    if state.play(mv) != Status::Ongoing {
+        *moves_left = 0;
+        return PuzzleStep{correct, solved: true, reply: None, moves_left: 0};
+    }
+    let reply = longest_defence(state, *moves_left).expect("the forced win is not over");
+    // every reply may lose straight away, e.g. by spelling the solver's word
+    let solved = state.play(reply) != Status::Ongoing;
+    if solved {
+        *moves_left = 0;
+    }
+    PuzzleStep{correct, solved, reply: Some(placed_in(&state.board, reply.0)), moves_left: *moves_left}
+}
+
+#[wasm_bindgen]
+// A puzzle being solved. Moves that keep the forced win are accepted even when they differ
+// from the stored solution, and the opponent answers with the reply that holds out longest.
+pub struct PuzzleSession {
+    game: Game,
+    moves_left: usize,
+}
+
+#[wasm_bindgen]
+impl PuzzleSession {
+    #[wasm_bindgen(constructor)]
+    // start solving the puzzle at the position, throws if win_in is not 1 to MAX_WIN_IN or its
+    // player to move cannot win in win_in moves
+    pub fn new(position: &str, win_in: usize) -> Result<PuzzleSession, JsValue> {
+        if win_in == 0 || win_in > MAX_WIN_IN {
+            return Err(JsValue::from(format!("puzzles are won in 1 to {} moves, not {}", MAX_WIN_IN, win_in)));
+        }
+        let mut game = notation::from_position_string(position)?;
+        let wins = match &mut game {
+            Game::Connect4(state) => !winning_moves(state, win_in).is_empty(),
+            Game::TootOtto(state) => !winning_moves(state, win_in).is_empty(),
+            Game::PopOut(_) => return Err(JsValue::from("there are no Pop Out puzzles")),
+        };
+        if !wins {
+            return Err(JsValue::from(format!("player {} cannot force a win in {} moves", game.to_move(), win_in)));
+        }
+        Ok(PuzzleSession{game, moves_left: win_in})
+    }
+
+    #[wasm_bindgen(unchecked_return_type = "Board")]
+    pub fn board(&self) -> JsValue {
+        js::to_js(self.game.board())
+    }
+
+    // id of the player solving the puzzle
+    pub fn to_move(&self) -> usize {
+        self.game.to_move()
+    }
+
+    // moves the solver has left to win in
+    pub fn moves_left(&self) -> usize {
+        self.moves_left
+    }
+
+    #[wasm_bindgen(unchecked_return_type = "PuzzleStep")]
+    // For Connect4 game
+    // try dropping a piece in the given column, throws if the puzzle is not a Connect4 one
+    #[allow(non_snake_case)]
+    pub fn play_C4(&mut self, col: usize) -> Result<JsValue, JsValue> {
+        match &mut self.game {
+            Game::Connect4(state) => {
+                let player_id = state.to_move;
+                Ok(js::to_js(&try_move(state, &mut self.moves_left, (col, player_id))))
+            },
+            _ => Err(JsValue::from("the puzzle is not a Connect4 puzzle")),
+        }
+    }
+
+    #[wasm_bindgen(unchecked_return_type = "PuzzleStep")]
+    // For Toot-Otto game
+    // try dropping the letter in the given column, throws if the puzzle is not a Toot-Otto one
+    #[allow(non_snake_case)]
+    pub fn play_TO(&mut self, col: usize, letter: String) -> Result<JsValue, JsValue> {
+        let letter = letter.chars().next().ok_or("no letter given")?.to_ascii_uppercase();
+        match &mut self.game {
+            Game::TootOtto(state) => Ok(js::to_js(&try_move(state, &mut self.moves_left, (col, letter)))),
+            _ => Err(JsValue::from("the puzzle is not a Toot-Otto puzzle")),
+        }
+    }
+}
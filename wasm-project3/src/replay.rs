@@ -0,0 +1,222 @@
+use wasm_bindgen::prelude::*;
+
+use crate::bot::{self, BotConfig, Position};
+use crate::evaluate::WIN_SCORE;
+use crate::js::{self, Placed, ReplayPosition};
+use crate::notation::{self, RecordResult};
+use crate::rules::{Connect4, GameMode, GameState, Rules, TootOtto};
+use crate::Board;
+
+// A recorded game that can be stepped through move by move. Ply 0 is the empty board and
+// ply N is the board after the first N moves.
+#[derive(Debug, Clone)]
+pub struct GameReplay<R: Rules> {
+    state: GameState<R>,
+    moves: Vec<(usize, R::Piece)>,
+    ply: usize,
+    // how the game ended after its last move
+    result: RecordResult,
+    // evaluations of the plies already looked at, with the engine settings they were found with
+    evaluations: Vec<Option<i32>>,
+    evaluated_with: Option<BotConfig>,
+}
+
+impl<R: Rules + Clone> GameReplay<R> {
+    // a replay of the moves played from the given empty board, starting at ply 0.
+    // Fails like notation::replay if a move is not legal
+    pub fn new(rules: R, board: Board, moves: Vec<(usize, R::Piece)>) -> Result<GameReplay<R>, String> {
+        let end = notation::replay(rules.clone(), board.clone(), &moves)?;
+        let result = notation::result_of(&end, moves.last());
+        let evaluations = vec![None; moves.len() + 1];
+        Ok(GameReplay{state: GameState::new(rules, board), moves, ply: 0, result, evaluations, evaluated_with: None})
+    }
+
+    pub fn ply(&self) -> usize {
+        self.ply
+    }
+    pub fn num_plies(&self) -> usize {
+        self.moves.len()
+    }
+    pub fn board(&self) -> &Board {
+        &self.state.board
+    }
+    pub fn to_move(&self) -> usize {
+        self.state.to_move
+    }
+    pub fn result(&self) -> RecordResult {
+        self.result
+    }
+
+    // play the next move, returns false if already at the end of the game
+    pub fn step_forward(&mut self) -> bool {
+        match self.moves.get(self.ply) {
+            Some(&mv) => {
+                self.state.play(mv);
+                self.ply += 1;
+                true
+            },
+            None => false,
+        }
+    }
+
+    // take back the last move, returns false if already at the start of the game
+    pub fn step_back(&mut self) -> bool {
+        if self.ply == 0 {
+            return false;
+        }
+        self.ply -= 1;
+        self.state.undo(self.moves[self.ply]);
+        true
+    }
+
+    // go to the given ply, or the end of the game if it has fewer plies
+    pub fn seek(&mut self, ply: usize) {
+        let ply = ply.min(self.moves.len());
+        while self.ply < ply {
+            self.step_forward();
+        }
+        while self.ply > ply {
+            self.step_back();
+        }
+    }
+
+    // the piece the last move dropped, None at ply 0
+    pub fn last_move(&self) -> Option<Placed> {
+        let (col, _) = *self.moves.get(self.ply.checked_sub(1)?)?;
+        let row = self.state.board.drop_row(col).map_or(1, |row| row + 1);
+        Placed::from_space(&self.state.board.get_piece(row, col))
+    }
+
+    // Engine evaluation of the board at the current ply from player 1's view, positive when
+    // player 1 is better. The finished game scores WIN_SCORE for the winner and 0 for a draw,
+    // other plies score the best move the engine finds for the player to move
+    pub fn evaluation(&mut self, config: &BotConfig) -> i32 {
+        if self.evaluated_with.as_ref() != Some(config) {
+            self.evaluations = vec![None; self.moves.len() + 1];
+            self.evaluated_with = Some(*config);
+        }
+        if let Some(score) = self.evaluations[self.ply] {
+            return score;
+        }
+        let score = match self.result {
+            RecordResult::Win(1) if self.ply == self.moves.len() => WIN_SCORE,
+            RecordResult::Win(_) if self.ply == self.moves.len() => -WIN_SCORE,
+            RecordResult::Draw if self.ply == self.moves.len() => 0,
+            _ => {
+                let score = bot::best_score(&mut self.state, config).unwrap_or(0);
+                if self.state.to_move == 1 { score } else { -score }
+            },
+        };
+        self.evaluations[self.ply] = Some(score);
+        score
+    }
+
+    // the board, last move and evaluation at the current ply for JS
+    fn position(&mut self, config: &BotConfig) -> ReplayPosition {
+        let evaluation = self.evaluation(config);
+        ReplayPosition{
+            ply: self.ply,
+            to_move: self.state.to_move,
+            board: self.state.board.clone(),
+            last_move: self.last_move(),
+            evaluation,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+enum ReplayedGame {
+    Connect4(GameReplay<Connect4>),
+    TootOtto(GameReplay<TootOtto>),
+}
+
+#[wasm_bindgen]
+// A recorded game to walk through move by move, with the engine's evaluation of every ply.
+// Stepping through it never touches the board of the game being played
+pub struct Replay {
+    game: ReplayedGame,
+    config: BotConfig,
+}
+
+impl Replay {
+    fn new(game: ReplayedGame) -> Replay {
+        // deep and exact, the evaluations do not depend on how fast the machine is
+        Replay{game, config: BotConfig{node_budget: 0, ..BotConfig::hard()}}
+    }
+}
+
+#[wasm_bindgen]
+impl Replay {
+    // For Connect4 game
+    // a replay of a game in column-sequence notation, throws if a move is not legal
+    #[allow(non_snake_case)]
+    pub fn from_notation_C4(notation: &str) -> Result<Replay, JsValue> {
+        let game = GameReplay::new(Connect4, Board::standard(GameMode::Connect4), notation::moves_C4(notation)?)?;
+        Ok(Replay::new(ReplayedGame::Connect4(game)))
+    }
+
+    // For Toot-Otto game
+    // a replay of a game in notation, throws if a move is not legal
+    #[allow(non_snake_case)]
+    pub fn from_notation_TO(notation: &str) -> Result<Replay, JsValue> {
+        let record = notation::parse_TO(notation)?;
+        let board = Board::standard(GameMode::TootOtto);
+        record.game(board.clone())?;
+        let game = GameReplay::new(record.rules(), board, record.moves)?;
+        Ok(Replay::new(ReplayedGame::TootOtto(game)))
+    }
+
+    // evaluate the plies with the given bot settings instead
+    pub fn set_engine(&mut self, config: &BotConfig) {
+        self.config = *config;
+    }
+
+    pub fn ply(&self) -> usize {
+        match &self.game {
+            ReplayedGame::Connect4(game) => game.ply(),
+            ReplayedGame::TootOtto(game) => game.ply(),
+        }
+    }
+
+    // number of moves in the game, the last ply
+    pub fn num_plies(&self) -> usize {
+        match &self.game {
+            ReplayedGame::Connect4(game) => game.num_plies(),
+            ReplayedGame::TootOtto(game) => game.num_plies(),
+        }
+    }
+
+    // play the next move, returns false if already at the end of the game
+    pub fn step_forward(&mut self) -> bool {
+        match &mut self.game {
+            ReplayedGame::Connect4(game) => game.step_forward(),
+            ReplayedGame::TootOtto(game) => game.step_forward(),
+        }
+    }
+
+    // take back the last move, returns false if already at the start of the game
+    pub fn step_back(&mut self) -> bool {
+        match &mut self.game {
+            ReplayedGame::Connect4(game) => game.step_back(),
+            ReplayedGame::TootOtto(game) => game.step_back(),
+        }
+    }
+
+    // go to the given ply, 0 is the empty board
+    pub fn seek(&mut self, ply: usize) {
+        match &mut self.game {
+            ReplayedGame::Connect4(game) => game.seek(ply),
+            ReplayedGame::TootOtto(game) => game.seek(ply),
+        }
+    }
+
+    #[wasm_bindgen(unchecked_return_type = "ReplayPosition")]
+    // the board, last move and evaluation at the current ply
+    pub fn position(&mut self) -> JsValue {
+        let position = match &mut self.game {
+            ReplayedGame::Connect4(game) => game.position(&self.config),
+            ReplayedGame::TootOtto(game) => game.position(&self.config),
+        };
+        js::to_js(&position)
+    }
+}
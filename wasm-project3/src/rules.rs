@@ -0,0 +1,680 @@
+use std::fmt::Debug;
+use std::ops::RangeInclusive;
+
+use serde::{Deserialize, Serialize};
+use tsify::Tsify;
+use wasm_bindgen::prelude::*;
+
+use crate::{Board, Player};
+use crate::evaluate;
+use crate::{C4_NUM_COLS, C4_NUM_ROWS, FIVE_NUM_COLS, FIVE_NUM_ROWS, TO_NUM_COLS, TO_NUM_ROWS, WIN_LEN_C4, WIN_LEN_FIVE, WIN_LEN_TO};
+
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+// The games that can be played, each one is played by the Rules of the same name
+pub enum GameMode {
+    Connect4,
+    TootOtto,
+    PopOut,
+    FiveInARow,
+    PowerUp,
+}
+
+impl GameMode {
+    // standard (num_rows, num_cols, win_len) of the mode's board
+    pub fn board_size(self) -> (usize, usize, usize) {
+        match self {
+            GameMode::Connect4 => Connect4.board_size(),
+            GameMode::TootOtto => TootOtto::default().board_size(),
+            GameMode::PopOut => PopOut.board_size(),
+            GameMode::FiveInARow => FiveInARow.board_size(),
+            GameMode::PowerUp => PowerUp::default().board_size(),
+        }
+    }
+
+    // name of the mode as shown to players
+    pub fn name(self) -> &'static str {
+        match self {
+            GameMode::Connect4 => "Connect4",
+            GameMode::TootOtto => "Toot-Otto",
+            GameMode::PopOut => "Pop Out",
+            GameMode::FiveInARow => "Five in a Row",
+            GameMode::PowerUp => "Power-Up",
+        }
+    }
+
+    // id of the mode in the variants registry, see variants::VARIANTS
+    pub fn id(self) -> &'static str {
+        match self {
+            GameMode::Connect4 => "connect4",
+            GameMode::TootOtto => "toot-otto",
+            GameMode::PopOut => "popout",
+            GameMode::FiveInARow => "five-in-a-row",
+            GameMode::PowerUp => "power-up",
+        }
+    }
+
+    // how the mode is played, in a sentence or two for players picking a variant
+    pub fn description(self) -> &'static str {
+        match self {
+            GameMode::Connect4 => "Drop your pieces in turn, the first to get 4 in a row wins.",
+            GameMode::TootOtto => "Drop T's and O's, one player wins by spelling TOOT and the other by spelling OTTO.",
+            GameMode::PopOut => "Connect4 where you may pop one of your pieces off the bottom of a column instead of dropping one.",
+            GameMode::FiveInARow => "Connect4 on a wider 7x9 board where it takes 5 in a row to win.",
+            GameMode::PowerUp => "Connect4 where each player also has an anvil that smashes the pieces below it, \
+                a bomb that blows up the top piece of a column and a wall that blocks lines for both players.",
+        }
+    }
+}
+
+// How a game is played on a Board. A new game mode implements this, gets a GameMode variant
+// and is listed in variants::VARIANTS.
+pub trait Rules {
+    // what gets dropped into a column
+    type Piece: Copy + PartialEq + Debug;
+    // how a finished game ended
+    type Outcome: Copy + PartialEq + Debug;
+
+    // the mode these rules belong to
+    fn mode(&self) -> GameMode;
+    // standard (num_rows, num_cols, win_len) of the board
+    fn board_size(&self) -> (usize, usize, usize);
+    // every piece the given player may still drop on the board
+    fn pieces(&self, board: &Board, player_id: usize) -> Vec<Self::Piece>;
+    // drop the given player's piece into a column, returns the row it landed in or None if the column is full
+    fn drop_piece(&mut self, board: &mut Board, col: usize, player_id: usize, piece: Self::Piece) -> Option<usize>;
+    // check whether the given player's move that landed at the given row and column finished the game
+    fn outcome(&self, board: &Board, row: usize, col: usize, player_id: usize) -> Option<Self::Outcome>;
+    // the piece at the given row and column, None if the space is empty
+    fn piece_at(&self, board: &Board, row: usize, col: usize) -> Option<Self::Piece>;
+    // id of the player a finished game was won by, None if nobody won it
+    fn winner(&self, outcome: Self::Outcome) -> Option<usize>;
+    // score the board from the view of the given player, higher is better for them
+    fn evaluate(&self, board: &Board, player_id: usize) -> i32;
+
+    // take back the given player's piece that was dropped into a column
+    fn undo_piece(&mut self, board: &mut Board, col: usize, _player_id: usize, _piece: Self::Piece) {
+        board.remove_top(col);
+    }
+
+    // true if the piece only fills the space it lands in, leaving the rest of its column alone
+    fn fills_one_space(&self, _piece: Self::Piece) -> bool {
+        true
+    }
+
+    // every move (column and piece) the given player may make
+    fn legal_moves(&self, board: &Board, player_id: usize) -> Vec<(usize, Self::Piece)> {
+        let pieces = self.pieces(board, player_id);
+        (1..=board.num_cols())
+            .filter(|&col| !board.is_col_full(col))
+            .flat_map(|col| pieces.iter().map(move |&piece| (col, piece)))
+            .collect()
+    }
+}
+
+// Players drop pieces of their own, the first with win_len in a row wins
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Connect4;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum OutcomeC4 {
+    // the player with this id got win_len in a row
+    Win(usize),
+    // the board filled up without a winner
+    Draw,
+}
+
+impl Rules for Connect4 {
+    type Piece = usize;  // id of the player the piece belongs to
+    type Outcome = OutcomeC4;
+
+    fn mode(&self) -> GameMode {
+        GameMode::Connect4
+    }
+
+    fn board_size(&self) -> (usize, usize, usize) {
+        (C4_NUM_ROWS, C4_NUM_COLS, WIN_LEN_C4)
+    }
+
+    fn pieces(&self, _board: &Board, player_id: usize) -> Vec<usize> {
+        vec![player_id]
+    }
+
+    fn drop_piece(&mut self, board: &mut Board, col: usize, _player_id: usize, piece: usize) -> Option<usize> {
+        board.drop_player(col, Player{id: piece})
+    }
+
+    fn outcome(&self, board: &Board, row: usize, col: usize, _player_id: usize) -> Option<OutcomeC4> {
+        let player_id = board.player_at(row, col)?;
+        if evaluate::is_win_at_C4(board, row, col, player_id) {
+            Some(OutcomeC4::Win(player_id))
+        } else if board.is_full() {
+            Some(OutcomeC4::Draw)
+        } else {
+            None
+        }
+    }
+
+    fn piece_at(&self, board: &Board, row: usize, col: usize) -> Option<usize> {
+        board.player_at(row, col)
+    }
+
+    fn winner(&self, outcome: OutcomeC4) -> Option<usize> {
+        match outcome {
+            OutcomeC4::Win(player_id) => Some(player_id),
+            OutcomeC4::Draw => None,
+        }
+    }
+
+    fn evaluate(&self, board: &Board, player_id: usize) -> i32 {
+        evaluate::evaluate_board_C4(board, player_id)
+    }
+}
+
+// Both players drop T's and O's, spelling TOOT or OTTO in a row wins for whoever owns the word
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TootOtto {
+    // id of the player who spells TOOT, the other player spells OTTO
+    toot_player: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum OutcomeTO {
+    // TOOT was spelled
+    Toot,
+    // OTTO was spelled
+    Otto,
+    // both words were spelled by the same move
+    Tie,
+    // the board filled up without either word
+    Draw,
+}
+
+impl Rules for TootOtto {
+    type Piece = char;  // 'T' or 'O'
+    type Outcome = OutcomeTO;
+
+    fn mode(&self) -> GameMode {
+        GameMode::TootOtto
+    }
+
+    fn board_size(&self) -> (usize, usize, usize) {
+        (TO_NUM_ROWS, TO_NUM_COLS, WIN_LEN_TO)
+    }
+
+    fn pieces(&self, board: &Board, player_id: usize) -> Vec<char> {
+        let (t_left, o_left) = self.letters_left(board, player_id);
+        let mut letters = vec![];
+        if t_left > 0 { letters.push('T'); }
+        if o_left > 0 { letters.push('O'); }
+        letters
+    }
+
+    fn drop_piece(&mut self, board: &mut Board, col: usize, player_id: usize, letter: char) -> Option<usize> {
+        board.drop_letter(col, letter.to_string(), Player{id: player_id})
+    }
+
+    // a move that spells both words ties, whoever made it, otherwise the word it spelled
+    // wins for its owner even if the mover spelled the opponent's word
+    fn outcome(&self, board: &Board, row: usize, col: usize, _player_id: usize) -> Option<OutcomeTO> {
+        let words = evaluate::words_through_TO(board, row, col);
+        let toot = words.iter().any(|word| word.word == "TOOT");
+        let otto = words.iter().any(|word| word.word == "OTTO");
+        match (toot, otto) {
+            (true, true) => Some(OutcomeTO::Tie),
+            (true, false) => Some(OutcomeTO::Toot),
+            (false, true) => Some(OutcomeTO::Otto),
+            (false, false) if board.is_full() => Some(OutcomeTO::Draw),
+            (false, false) => None,
+        }
+    }
+
+    fn piece_at(&self, board: &Board, row: usize, col: usize) -> Option<char> {
+        board.letter_at(row, col)
+    }
+
+    // whoever owns the word that was spelled wins
+    fn winner(&self, outcome: OutcomeTO) -> Option<usize> {
+        match outcome {
+            OutcomeTO::Toot => Some(self.owner("TOOT")),
+            OutcomeTO::Otto => Some(self.owner("OTTO")),
+            OutcomeTO::Tie | OutcomeTO::Draw => None,
+        }
+    }
+
+    fn evaluate(&self, board: &Board, player_id: usize) -> i32 {
+        evaluate::evaluate_board_TO(board, self.word(player_id))
+    }
+}
+
+impl TootOtto {
+    // rules where the given player spells TOOT and the other player spells OTTO
+    pub fn new(toot_player: usize) -> TootOtto {
+        if toot_player != 1 && toot_player != 2 {
+            panic!("TOOT must be spelled by player 1 or 2!");
+        }
+        TootOtto{toot_player}
+    }
+
+    // id of the player who spells TOOT
+    pub fn toot_player(&self) -> usize {
+        self.toot_player
+    }
+
+    // the word the given player spells
+    pub fn word(&self, player_id: usize) -> &'static str {
+        if player_id == self.toot_player { "TOOT" } else { "OTTO" }
+    }
+
+    // id of the player who spells the given word
+    pub fn owner(&self, word: &str) -> usize {
+        if word == "TOOT" { self.toot_player } else { 3 - self.toot_player }
+    }
+
+    // number of T's and of O's each player starts with, six on the standard board
+    // and a quarter of the spaces on other boards so the letters last until it is full
+    pub fn letters_each(&self, board: &Board) -> usize {
+        (board.num_rows() * board.num_cols()).div_ceil(4)
+    }
+
+    // number of (T's, O's) the given player has not dropped yet
+    pub fn letters_left(&self, board: &Board, player_id: usize) -> (usize, usize) {
+        let each = self.letters_each(board);
+        let (mut t_used, mut o_used) = (0, 0);
+        for col in 1..=board.num_cols() {
+            for row in 1..=board.num_rows() {
+                if board.player_at(row, col) != Some(player_id) {
+                    continue;
+                }
+                match board.letter_at(row, col) {
+                    Some('T') => t_used += 1,
+                    Some('O') => o_used += 1,
+                    _ => {},
+                }
+            }
+        }
+        (each.saturating_sub(t_used), each.saturating_sub(o_used))
+    }
+}
+
+// player 1 spells TOOT unless the players switched words
+impl Default for TootOtto {
+    fn default() -> TootOtto {
+        TootOtto::new(1)
+    }
+}
+
+// Connect4 where a player may instead pop one of their own pieces off the bottom of a column,
+// the pieces above it falling down a row. A pop that lines up pieces of both players wins for
+// the player who popped. A full board is not a draw while the player to move has a piece
+// on the bottom row to pop.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PopOut;
+
+// What a Pop Out player does in a column
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PopOutMove {
+    // drop a piece on top of the column
+    Drop,
+    // pop the player's own piece off the bottom of the column
+    Pop,
+}
+
+impl Rules for PopOut {
+    type Piece = PopOutMove;
+    type Outcome = OutcomeC4;
+
+    fn mode(&self) -> GameMode {
+        GameMode::PopOut
+    }
+
+    fn board_size(&self) -> (usize, usize, usize) {
+        (C4_NUM_ROWS, C4_NUM_COLS, WIN_LEN_C4)
+    }
+
+    fn pieces(&self, board: &Board, player_id: usize) -> Vec<PopOutMove> {
+        if self.can_pop_any(board, player_id) {
+            vec![PopOutMove::Drop, PopOutMove::Pop]
+        } else {
+            vec![PopOutMove::Drop]
+        }
+    }
+
+    // returns the bottom row for a pop, None if the column is full or the bottom piece
+    // is not the player's
+    fn drop_piece(&mut self, board: &mut Board, col: usize, player_id: usize, piece: PopOutMove) -> Option<usize> {
+        match piece {
+            PopOutMove::Drop => board.drop_player(col, Player{id: player_id}),
+            PopOutMove::Pop if self.can_pop(board, col, player_id) => {
+                board.pop_bottom(col);
+                Some(board.num_rows())
+            },
+            PopOutMove::Pop => None,
+        }
+    }
+
+    fn undo_piece(&mut self, board: &mut Board, col: usize, player_id: usize, piece: PopOutMove) {
+        match piece {
+            PopOutMove::Drop => board.remove_top(col),
+            PopOutMove::Pop => board.push_bottom(col, Player{id: player_id}),
+        }
+    }
+
+    // a pop moves the whole column down a row
+    fn fills_one_space(&self, piece: PopOutMove) -> bool {
+        piece == PopOutMove::Drop
+    }
+
+    // a pop moves every piece in its column, so lines through all of them are checked
+    fn outcome(&self, board: &Board, _row: usize, col: usize, player_id: usize) -> Option<OutcomeC4> {
+        let mut winners = vec![];
+        for row in 1..=board.num_rows() {
+            if let Some(id) = board.player_at(row, col) {
+                if !winners.contains(&id) && evaluate::is_win_at_C4(board, row, col, id) {
+                    winners.push(id);
+                }
+            }
+        }
+        if winners.contains(&player_id) {
+            Some(OutcomeC4::Win(player_id))
+        } else if let Some(&other) = winners.first() {
+            Some(OutcomeC4::Win(other))
+        } else if board.is_full() && !self.can_pop_any(board, 3 - player_id) {
+            Some(OutcomeC4::Draw)
+        } else {
+            None
+        }
+    }
+
+    // a piece on the board is one that was dropped
+    fn piece_at(&self, board: &Board, row: usize, col: usize) -> Option<PopOutMove> {
+        board.player_at(row, col).map(|_| PopOutMove::Drop)
+    }
+
+    fn winner(&self, outcome: OutcomeC4) -> Option<usize> {
+        Connect4.winner(outcome)
+    }
+
+    fn evaluate(&self, board: &Board, player_id: usize) -> i32 {
+        evaluate::evaluate_board_C4(board, player_id)
+    }
+
+    // drops into columns with space and pops from columns with the player's piece at the bottom
+    fn legal_moves(&self, board: &Board, player_id: usize) -> Vec<(usize, PopOutMove)> {
+        let drops = (1..=board.num_cols()).filter(|&col| !board.is_col_full(col)).map(|col| (col, PopOutMove::Drop));
+        let pops = (1..=board.num_cols()).filter(|&col| self.can_pop(board, col, player_id)).map(|col| (col, PopOutMove::Pop));
+        drops.chain(pops).collect()
+    }
+}
+
+impl PopOut {
+    // return true if the bottom piece of the given column is the player's
+    pub fn can_pop(&self, board: &Board, col: usize, player_id: usize) -> bool {
+        board.player_at(board.num_rows(), col) == Some(player_id)
+    }
+
+    // return true if the player has a piece on the bottom row
+    pub fn can_pop_any(&self, board: &Board, player_id: usize) -> bool {
+        (1..=board.num_cols()).any(|col| self.can_pop(board, col, player_id))
+    }
+}
+
+// Connect4 with 5 in a row to win on a 7x9 board. The Connect4 rules already go by the board's
+// win length, these only change the mode and the board size
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FiveInARow;
+
+impl Rules for FiveInARow {
+    type Piece = usize;
+    type Outcome = OutcomeC4;
+
+    fn mode(&self) -> GameMode {
+        GameMode::FiveInARow
+    }
+
+    fn board_size(&self) -> (usize, usize, usize) {
+        (FIVE_NUM_ROWS, FIVE_NUM_COLS, WIN_LEN_FIVE)
+    }
+
+    fn pieces(&self, board: &Board, player_id: usize) -> Vec<usize> {
+        Connect4.pieces(board, player_id)
+    }
+
+    fn drop_piece(&mut self, board: &mut Board, col: usize, player_id: usize, piece: usize) -> Option<usize> {
+        Connect4.drop_piece(board, col, player_id, piece)
+    }
+
+    fn outcome(&self, board: &Board, row: usize, col: usize, player_id: usize) -> Option<OutcomeC4> {
+        Connect4.outcome(board, row, col, player_id)
+    }
+
+    fn piece_at(&self, board: &Board, row: usize, col: usize) -> Option<usize> {
+        Connect4.piece_at(board, row, col)
+    }
+
+    fn winner(&self, outcome: OutcomeC4) -> Option<usize> {
+        Connect4.winner(outcome)
+    }
+
+    fn evaluate(&self, board: &Board, player_id: usize) -> i32 {
+        Connect4.evaluate(board, player_id)
+    }
+}
+
+// Connect4 where each player also gets one of each power piece:
+//   - an anvil falls through its column, smashing every piece in it, and lands on the bottom row
+//   - a bomb blows up the top piece of its column and takes its place
+//   - a wall fills a space for neither player, blocking lines through it
+// Anvils and bombs stay on the board as their player's pieces. A power piece is used up once
+// played, even if the opponent smashes or blows it up. The rules keep which pieces are powers
+// and what was smashed, so the board only holds the players' pieces and the walls
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PowerUp {
+    // (player id, power) of every power piece played, smashed ones included
+    played: Vec<(usize, Power)>,
+    // (row, col, power) of the power pieces on the board
+    on_board: Vec<(usize, usize, Power)>,
+    // the pieces each anvil or bomb smashed, the last one played on top, so it can be taken back
+    smashed: Vec<Vec<Smashed>>,
+}
+
+// serialized by its name, e.g. "anvil"
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Tsify)]
+#[serde(rename_all = "lowercase")]
+pub enum Power {
+    Disc,
+    Anvil,
+    Bomb,
+    Wall,
+}
+
+impl Power {
+    pub const ALL: [Power; 4] = [Power::Disc, Power::Anvil, Power::Bomb, Power::Wall];
+    // the power pieces each player gets one of
+    pub const POWERS: [Power; 3] = [Power::Anvil, Power::Bomb, Power::Wall];
+
+    // the name JS passes for the power, e.g. "anvil", the same as it is serialized as
+    pub fn name(self) -> &'static str {
+        match self {
+            Power::Disc => "disc",
+            Power::Anvil => "anvil",
+            Power::Bomb => "bomb",
+            Power::Wall => "wall",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Power> {
+        Power::ALL.iter().copied().find(|power| power.name() == name)
+    }
+}
+
+// A piece an anvil or bomb smashed, with the id it was on the board with
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Smashed {
+    row: usize,
+    id: usize,
+    power: Power,
+}
+
+impl Rules for PowerUp {
+    type Piece = Power;
+    type Outcome = OutcomeC4;
+
+    fn mode(&self) -> GameMode {
+        GameMode::PowerUp
+    }
+
+    fn board_size(&self) -> (usize, usize, usize) {
+        (C4_NUM_ROWS, C4_NUM_COLS, WIN_LEN_C4)
+    }
+
+    fn pieces(&self, _board: &Board, player_id: usize) -> Vec<Power> {
+        let mut pieces = vec![Power::Disc];
+        pieces.extend(self.powers_left(player_id));
+        pieces
+    }
+
+    // returns the row the piece ended up in, None if the power cannot be played in the column
+    fn drop_piece(&mut self, board: &mut Board, col: usize, player_id: usize, power: Power) -> Option<usize> {
+        if !self.can_play(board, col, power) {
+            return None;
+        }
+        let row = match power {
+            Power::Disc | Power::Wall => board.drop_row(col)?,
+            Power::Anvil => {
+                let num_rows = board.num_rows();
+                self.smash(board, 1..=num_rows, col);
+                num_rows
+            },
+            Power::Bomb => {
+                let row = PowerUp::top_row(board, col);
+                self.smash(board, row..=row, col);
+                row
+            },
+        };
+        let id = if power == Power::Wall { PowerUp::WALL } else { player_id };
+        board.set_piece_player(row, col, Player{id});
+        if power != Power::Disc {
+            self.played.push((player_id, power));
+            self.on_board.push((row, col, power));
+        }
+        Some(row)
+    }
+
+    fn undo_piece(&mut self, board: &mut Board, col: usize, player_id: usize, power: Power) {
+        let top = PowerUp::top_row(board, col);
+        board.clear_piece(top, col);
+        if power == Power::Disc {
+            return;
+        }
+        self.played.retain(|&played| played != (player_id, power));
+        self.on_board.retain(|&(row, c, _)| (row, c) != (top, col));
+        if power == Power::Anvil || power == Power::Bomb {
+            for piece in self.smashed.pop().unwrap_or_default() {
+                board.set_piece_player(piece.row, col, Player{id: piece.id});
+                if piece.power != Power::Disc {
+                    self.on_board.push((piece.row, col, piece.power));
+                }
+            }
+        }
+    }
+
+    // anvils and bombs smash the pieces below them
+    fn fills_one_space(&self, power: Power) -> bool {
+        power == Power::Disc || power == Power::Wall
+    }
+
+    // the piece that was played is always the top one of its column, a wall wins for nobody
+    fn outcome(&self, board: &Board, _row: usize, col: usize, _player_id: usize) -> Option<OutcomeC4> {
+        let top = PowerUp::top_row(board, col);
+        match board.player_at(top, col) {
+            Some(player_id) if player_id != PowerUp::WALL && evaluate::is_win_at_C4(board, top, col, player_id) => {
+                Some(OutcomeC4::Win(player_id))
+            },
+            _ if board.is_full() => Some(OutcomeC4::Draw),
+            _ => None,
+        }
+    }
+
+    fn piece_at(&self, board: &Board, row: usize, col: usize) -> Option<Power> {
+        board.player_at(row, col)?;
+        Some(self.power_at(row, col))
+    }
+
+    fn winner(&self, outcome: OutcomeC4) -> Option<usize> {
+        Connect4.winner(outcome)
+    }
+
+    fn evaluate(&self, board: &Board, player_id: usize) -> i32 {
+        evaluate::evaluate_board_C4(board, player_id)
+    }
+
+    fn legal_moves(&self, board: &Board, player_id: usize) -> Vec<(usize, Power)> {
+        let pieces = self.pieces(board, player_id);
+        (1..=board.num_cols())
+            .flat_map(|col| pieces.iter().filter(move |&&power| self.can_play(board, col, power)).map(move |&power| (col, power)))
+            .collect()
+    }
+}
+
+impl PowerUp {
+    // the id a wall is put on the board with, it belongs to neither player
+    pub const WALL: usize = 3;
+
+    // the power pieces the given player has not played yet
+    pub fn powers_left(&self, player_id: usize) -> Vec<Power> {
+        Power::POWERS.iter().copied().filter(|&power| !self.played.contains(&(player_id, power))).collect()
+    }
+
+    // (row, col, power) of every anvil, bomb and wall on the board
+    pub fn powers_on_board(&self) -> &[(usize, usize, Power)] {
+        &self.on_board
+    }
+
+    // return true if the power can be played in the column: discs and walls need space in it,
+    // anvils and bombs need pieces in it to smash
+    pub fn can_play(&self, board: &Board, col: usize, power: Power) -> bool {
+        match power {
+            Power::Disc | Power::Wall => !board.is_col_full(col),
+            Power::Anvil | Power::Bomb => board.drop_row(col) != Some(board.num_rows()),
+        }
+    }
+
+    // the power of the piece at the given row and column, a disc unless a power piece was played there
+    fn power_at(&self, row: usize, col: usize) -> Power {
+        self.on_board.iter().find(|&&(r, c, _)| (r, c) == (row, col)).map_or(Power::Disc, |&(_, _, power)| power)
+    }
+
+    // the row of the top piece of the column, 1 when it is full
+    fn top_row(board: &Board, col: usize) -> usize {
+        board.drop_row(col).map_or(1, |row| row + 1)
+    }
+
+    // clear the pieces in the given rows of the column, keeping them to put back on undo
+    fn smash(&mut self, board: &mut Board, rows: RangeInclusive<usize>, col: usize) {
+        let mut smashed = vec![];
+        for row in rows.clone() {
+            if let Some(id) = board.player_at(row, col) {
+                smashed.push(Smashed{row, id, power: self.power_at(row, col)});
+                board.clear_piece(row, col);
+            }
+        }
+        self.on_board.retain(|&(row, c, _)| c != col || !rows.contains(&row));
+        self.smashed.push(smashed);
+    }
+}
+
+// A board being played by some Rules, with the id of the player to move
+#[derive(Debug, Clone)]
+pub struct GameState<R: Rules> {
+    pub rules: R,
+    pub board: Board,
+    pub to_move: usize,
+}
+
+impl<R: Rules> GameState<R> {
+    // a game on the given board, player 1 moving first
+    pub fn new(rules: R, board: Board) -> GameState<R> {
+        GameState{rules, board, to_move: 1}
+    }
+}
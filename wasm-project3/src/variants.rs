@@ -0,0 +1,42 @@
+// The variants players can pick from. Each one is a GameMode played by the Rules object of the
+// same name, see rules.rs, and is picked by its id with set_variant.
+
+use wasm_bindgen::prelude::*;
+
+use crate::js::{self, Variant};
+use crate::rules::GameMode;
+
+// Every variant, in the order they are offered to players.
+// Pop 10 is not one of them: a player who pops a piece out of a line of 4 keeps it and moves
+// again, and the bots only search games where the players take turns one move at a time
+pub const VARIANTS: [GameMode; 5] = [
+    GameMode::Connect4,
+    GameMode::TootOtto,
+    GameMode::PopOut,
+    GameMode::FiveInARow,
+    GameMode::PowerUp,
+];
+
+// the variant with the given id, e.g. "five-in-a-row"
+pub fn find(id: &str) -> Option<GameMode> {
+    VARIANTS.iter().copied().find(|mode| mode.id() == id)
+}
+
+// same as find, with an error naming the id when there is no such variant
+pub fn parse(id: &str) -> Result<GameMode, String> {
+    find(id).ok_or(format!("unknown variant '{}'", id))
+}
+
+#[wasm_bindgen(unchecked_return_type = "Variant[]")]
+// Get every variant that can be played, with its id, name, description and board size
+pub fn available_variants() -> JsValue {
+    js::to_js(&VARIANTS.iter().map(|&mode| Variant::new(mode)).collect::<Vec<Variant>>())
+}
+
+#[wasm_bindgen]
+// Set the variant to play by its id from available_variants, throws if there is no such variant.
+// Replaces set_game, create the board with new_board afterwards
+pub fn set_variant(id: &str) -> Result<(), JsValue> {
+    crate::set_game(parse(id)?);
+    Ok(())
+}
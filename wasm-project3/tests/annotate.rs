@@ -0,0 +1,61 @@
+//! Tests for annotating the moves of finished games, run natively.
+
+use wasm_project3::annotate::{self, MoveQuality};
+use wasm_project3::bot::BotConfig;
+use wasm_project3::evaluate::WIN_SCORE;
+use wasm_project3::js::Piece;
+use wasm_project3::rules::GameMode;
+
+fn engine() -> BotConfig {
+    BotConfig{node_budget: 0, depth: 3, ..BotConfig::medium()}
+}
+
+#[test]
+fn moves_are_classified_by_evaluation_drop() {
+    assert_eq!(MoveQuality::classify(40, 40), MoveQuality::Best);
+    assert_eq!(MoveQuality::classify(40, 25), MoveQuality::Good);
+    assert_eq!(MoveQuality::classify(40, -10), MoveQuality::Inaccuracy);
+    assert_eq!(MoveQuality::classify(40, -100), MoveQuality::Mistake);
+    assert_eq!(MoveQuality::classify(WIN_SCORE + 3, 10), MoveQuality::Blunder);
+    assert_eq!(MoveQuality::classify(0, -WIN_SCORE), MoveQuality::Blunder);
+    // a slower win and a position lost whatever was played are not held against the player
+    assert_eq!(MoveQuality::classify(WIN_SCORE + 3, WIN_SCORE + 1), MoveQuality::Good);
+    assert_eq!(MoveQuality::classify(-WIN_SCORE - 1, -WIN_SCORE - 3), MoveQuality::Good);
+}
+
+#[test]
+fn missed_block_and_missed_win_are_blunders() {
+    // player 1 lines up three along the bottom, player 2 plays column 7 instead of blocking
+    // column 4 and player 1 then misses the win by playing column 6
+    let annotations = annotate::annotate_notation(GameMode::Connect4, "11223764", &engine()).unwrap();
+    assert_eq!(annotations.len(), 8);
+
+    let missed_block = &annotations[5];
+    assert_eq!((missed_block.ply, missed_block.player_id, missed_block.col), (6, 2, 7));
+    assert_eq!(missed_block.best_col, 4);
+    assert_eq!(missed_block.best_piece, Piece::Player(2));
+    assert_eq!(missed_block.quality, MoveQuality::Blunder);
+
+    let missed_win = &annotations[6];
+    assert_eq!((missed_win.player_id, missed_win.col, missed_win.best_col), (1, 6, 4));
+    assert!(missed_win.before >= WIN_SCORE);
+    assert_eq!(missed_win.quality, MoveQuality::Blunder);
+}
+
+#[test]
+fn winning_move_is_best() {
+    let annotations = annotate::annotate_notation(GameMode::Connect4, "1122334", &engine()).unwrap();
+    let last = annotations.last().unwrap();
+    assert_eq!(last.col, 4);
+    assert_eq!(last.quality, MoveQuality::Best);
+}
+
+#[test]
+fn toot_otto_games_are_annotated() {
+    let notation = "[Toot \"1\"]\n3T 4O 3O 2T";
+    let annotations = annotate::annotate_notation(GameMode::TootOtto, notation, &engine()).unwrap();
+    assert_eq!(annotations.len(), 4);
+    assert_eq!(annotations[1].piece, Piece::Letter('O'));
+    assert_eq!(annotations[1].player_id, 2);
+    assert!(annotate::annotate_notation(GameMode::PopOut, "", &engine()).is_err());
+}
@@ -0,0 +1,96 @@
+//! Tests for the lines of spaces the board walks and the values passed to JS, run natively.
+
+use wasm_project3::js::{Piece, Placed};
+use wasm_project3::rules::{Connect4, Rules, TootOtto};
+use wasm_project3::Board;
+
+// Every space on the board where keep is true, sorted by the given key
+fn brute_force(board: &Board, keep: impl Fn(usize, usize) -> bool, key: impl Fn(&(usize, usize)) -> usize) -> Vec<(usize, usize)> {
+    let mut spaces = vec![];
+    for row in 1..=board.num_rows() {
+        for col in 1..=board.num_cols() {
+            if keep(row, col) {
+                spaces.push((row, col));
+            }
+        }
+    }
+    spaces.sort_by_key(key);
+    spaces
+}
+
+#[test]
+fn lines_match_brute_force_on_every_board() {
+    for num_rows in 1..=9 {
+        for num_cols in 1..=9 {
+            let board = Board::with_size(num_rows, num_cols, 2);
+            for row in 1..=num_rows {
+                for col in 1..=num_cols {
+                    let at = format!("R{}C{} on {}x{}", row, col, num_rows, num_cols);
+                    assert_eq!(board.row_spaces(row), brute_force(&board, |r, _| r == row, |&(_, c)| c), "row {}", at);
+                    assert_eq!(board.col_spaces(col), brute_force(&board, |_, c| c == col, |&(r, _)| r), "col {}", at);
+                    assert_eq!(
+                        board.right_diagonal_spaces(row, col),
+                        brute_force(&board, |r, c| r + c == row + col, |&(_, c)| c),
+                        "right diagonal {}", at,
+                    );
+                    assert_eq!(
+                        board.left_diagonal_spaces(row, col),
+                        brute_force(&board, |r, c| r + col == c + row, |&(_, c)| c),
+                        "left diagonal {}", at,
+                    );
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn diagonal_ids_follow_diagonal_spaces() {
+    // fill the board column by column so every space has a known id
+    let mut board = Board::with_size(6, 7, 4);
+    for col in 1..=7 {
+        for i in 0..6 {
+            let player_id = 1 + (col + i) % 2;
+            Connect4.drop_piece(&mut board, col, player_id, player_id);
+        }
+    }
+    for row in 1..=6 {
+        for col in 1..=7 {
+            let ids: Vec<usize> = board.left_diagonal_spaces(row, col).iter()
+                .map(|&(r, c)| board.player_at(r, c).unwrap())
+                .collect();
+            assert_eq!(board.get_ids_in_left_diagonal(row, col), ids);
+            let ids: Vec<usize> = board.right_diagonal_spaces(row, col).iter()
+                .map(|&(r, c)| board.player_at(r, c).unwrap())
+                .collect();
+            assert_eq!(board.get_ids_in_right_diagonal(row, col), ids);
+        }
+    }
+}
+
+#[test]
+fn pieces_serialize_with_named_fields() {
+    let mut board = Board::with_size(4, 6, 4);
+    TootOtto::default().drop_piece(&mut board, 3, 2, 'O');
+    let placed = Placed::all(&board);
+    assert_eq!(placed, vec![Placed{row: 4, col: 3, piece: Piece::Letter('O')}]);
+    assert_eq!(serde_json::to_string(&placed[0]).unwrap(), r#"{"row":4,"col":3,"piece":"O"}"#);
+    let placed = Placed{row: 6, col: 4, piece: Piece::Player(1)};
+    assert_eq!(serde_json::to_string(&placed).unwrap(), r#"{"row":6,"col":4,"piece":1}"#);
+
+    // the board comes back the same after a round trip
+    let json = serde_json::to_string(&board).unwrap();
+    let back: Board = serde_json::from_str(&json).unwrap();
+    assert_eq!(back.letter_at(4, 3), Some('O'));
+    assert_eq!(back.player_at(4, 3), Some(2));
+    assert_eq!(serde_json::to_string(&back).unwrap(), json);
+}
+
+#[test]
+fn boards_need_a_row_a_column_and_a_win_length_of_2() {
+    assert!(Board::check_size(6, 7, 4).is_ok());
+    assert!(Board::check_size(1, 1, 2).is_ok());
+    assert!(Board::check_size(0, 7, 4).is_err());
+    assert!(Board::check_size(6, 0, 4).is_err());
+    assert!(Board::check_size(6, 7, 1).is_err());
+}
@@ -0,0 +1,196 @@
+//! Tests for the bots, run natively on Connect4 positions.
+
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use wasm_project3::bot::{self, BotConfig, Position, Status};
+use wasm_project3::evaluate::WIN_SCORE;
+use wasm_project3::rules::{Connect4, GameMode, GameState, Rules, TootOtto};
+use wasm_project3::{get_seed, new_board, set_game, set_next_game_seed, Board};
+
+// Connect4 position after the given columns were played, player 1 moving first
+fn position(cols: &[usize]) -> GameState<Connect4> {
+    sized_position(6, 7, 4, cols)
+}
+
+// Same as position, on a board of the given size and win length
+fn sized_position(num_rows: usize, num_cols: usize, win_len: usize, cols: &[usize]) -> GameState<Connect4> {
+    let mut pos = GameState::new(Connect4, Board::with_size(num_rows, num_cols, win_len));
+    for &col in cols {
+        pos.play(drop(&pos, col));
+    }
+    pos
+}
+
+// The move dropping a piece of the player to move into the given column
+fn drop(pos: &GameState<Connect4>, col: usize) -> (usize, usize) {
+    (col, pos.to_move)
+}
+
+// Play a whole game between two bots and return the columns played
+fn bot_game(seed: u64, bots: [BotConfig; 2]) -> Vec<usize> {
+    bot_opening(seed, bots, usize::MAX)
+}
+
+// Same as bot_game, stopping after the given number of moves
+fn bot_opening(seed: u64, bots: [BotConfig; 2], num_moves: usize) -> Vec<usize> {
+    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+    let mut pos = position(&[]);
+    let mut cols = vec![];
+    while cols.len() < num_moves {
+        let (col, player_id) = match bot::choose_move(&mut pos, &bots[cols.len() % 2], &mut rng) {
+            Some(mv) => mv,
+            None => break,
+        };
+        cols.push(col);
+        if pos.play((col, player_id)) != Status::Ongoing {
+            break;
+        }
+    }
+    cols
+}
+
+#[test]
+fn same_seed_replays_same_game() {
+    let medium = BotConfig{node_budget: 0, ..BotConfig::medium()};
+    let easy = BotConfig{node_budget: 0, ..BotConfig::easy()};
+    for seed in 0..5 {
+        assert_eq!(bot_game(seed, [medium, easy]), bot_game(seed, [medium, easy]));
+    }
+    assert_ne!(bot_game(1, [easy, easy]), bot_game(2, [easy, easy]));
+}
+
+#[test]
+fn presets_replay_same_game_from_same_seed() {
+    let presets = [BotConfig::easy(), BotConfig::medium(), BotConfig::hard(), BotConfig::expert()];
+    // no clock in the presets, their searches stop after the same positions on any machine
+    assert!(presets.iter().all(|preset| preset.time_budget_ms == 0.0 && preset.node_budget > 0));
+    assert_eq!(bot_game(0, [presets[0], presets[1]]), bot_game(0, [presets[0], presets[1]]));
+    // deeper searches are slow unoptimized, so only their first moves are compared
+    for (seed, bots) in [[presets[2], presets[1]], [presets[3], presets[2]]].iter().enumerate() {
+        assert_eq!(bot_opening(seed as u64, *bots, 2), bot_opening(seed as u64, *bots, 2));
+    }
+}
+
+#[test]
+fn new_games_draw_fresh_seeds_unless_one_is_set() {
+    set_game(GameMode::Connect4);
+    set_next_game_seed(42);
+    new_board();
+    assert_eq!(get_seed(), 42);
+    // the seed was for that game only
+    let seeds: Vec<u32> = (0..3).map(|_| {
+        new_board();
+        get_seed()
+    }).collect();
+    assert!(seeds.iter().any(|&seed| seed != 42));
+    assert!(seeds.windows(2).any(|pair| pair[0] != pair[1]));
+}
+
+#[test]
+fn presets_search_deeper_and_slip_less() {
+    let presets = [BotConfig::easy(), BotConfig::medium(), BotConfig::hard(), BotConfig::expert()];
+    for pair in presets.windows(2) {
+        assert!(pair[0].depth < pair[1].depth);
+        assert!(pair[0].blunder_chance > pair[1].blunder_chance);
+    }
+    assert_eq!(BotConfig::preset("hard"), Some(BotConfig::hard()));
+    assert_eq!(BotConfig::preset("impossible"), None);
+}
+
+#[test]
+fn search_finds_win_in_one_and_two() {
+    // search alone, without the tactical layer picking the win for it
+    let search = |depth| BotConfig::new(depth, 0.0, 0.0, false, 0.0);
+    let mut rng = ChaCha8Rng::seed_from_u64(0);
+    // player 1 has columns 1-3 on the bottom row
+    let mut pos = position(&[1, 1, 2, 2, 3, 3]);
+    assert_eq!(bot::choose_move(&mut pos, &search(1), &mut rng), Some(drop(&pos, 4)));
+    let moves = pos.legal_moves();
+    let scored = bot::score_moves(&mut pos, &moves, &search(1));
+    let best = scored.iter().max_by_key(|&&(_, score)| score).unwrap();
+    assert_eq!(best.0, drop(&pos, 4));
+    assert!(best.1 >= WIN_SCORE);
+
+    // player 1 has columns 2 and 3, a piece in column 4 threatens both ends of the row
+    let mut pos = position(&[2, 7, 3, 7]);
+    assert_eq!(bot::choose_move(&mut pos, &search(3), &mut rng), Some(drop(&pos, 4)));
+    assert!(bot::best_score(&mut pos, &search(3)).unwrap() >= WIN_SCORE);
+    assert!(bot::best_score(&mut pos, &search(1)).unwrap() < WIN_SCORE);
+}
+
+#[test]
+fn blunders_miss_wins_search_finds() {
+    let mut pos = position(&[1, 1, 2, 2, 3, 3]);
+    let careful = BotConfig::new(1, 0.0, 0.0, false, 0.0);
+    let careless = BotConfig{blunder_chance: 1.0, ..careful};
+    let mut rng = ChaCha8Rng::seed_from_u64(0);
+    let mut missed = 0;
+    for _ in 0..30 {
+        assert_eq!(bot::choose_move(&mut pos, &careful, &mut rng), Some(drop(&pos, 4)));
+        if bot::choose_move(&mut pos, &careless, &mut rng) != Some(drop(&pos, 4)) {
+            missed += 1;
+        }
+    }
+    assert!(missed > 0);
+}
+
+#[test]
+fn tactics_take_immediate_win() {
+    // player 1 has columns 1-3 on the bottom row and is to move
+    let mut pos = position(&[1, 1, 2, 2, 3, 3]);
+    assert_eq!(bot::tactical_moves(&mut pos), vec![drop(&pos, 4)]);
+}
+
+#[test]
+fn tactics_block_immediate_loss() {
+    // player 1 threatens column 4, player 2 is to move
+    let mut pos = position(&[1, 7, 2, 7, 3]);
+    assert_eq!(bot::tactical_moves(&mut pos), vec![drop(&pos, 4)]);
+    let always_blunders = BotConfig::new(0, 0.0, 1.0, true, 0.0);
+    let mut rng = ChaCha8Rng::seed_from_u64(0);
+    for _ in 0..20 {
+        assert_eq!(bot::choose_move(&mut pos, &always_blunders, &mut rng), Some(drop(&pos, 4)));
+    }
+}
+
+#[test]
+fn tactics_never_fill_space_under_opponent_win() {
+    // player 1 has columns 1-3 on the second row, a piece in column 4 lets them complete it
+    let mut pos = position(&[3, 1, 1, 2, 2, 7, 3]);
+    let cols: Vec<usize> = bot::tactical_moves(&mut pos).iter().map(|&(col, _)| col).collect();
+    assert_eq!(cols, vec![1, 2, 3, 5, 6, 7]);
+}
+
+// Toot-Otto position with the given letters dropped left to right along the bottom row,
+// '-' leaving the column empty, player 1 spelling TOOT and to move
+fn toot_otto_row(letters: &str) -> GameState<TootOtto> {
+    let mut board = Board::with_size(4, 6, 4);
+    for (i, letter) in letters.chars().enumerate() {
+        if letter != '-' {
+            TootOtto::default().drop_piece(&mut board, i + 1, 1 + i % 2, letter);
+        }
+    }
+    GameState::new(TootOtto::default(), board)
+}
+
+#[test]
+fn tactics_spell_own_word_and_never_the_opponents() {
+    let mut pos = toot_otto_row("TOO---");
+    assert_eq!(bot::tactical_moves(&mut pos), vec![(4, 'T')]);
+    // an O in column 4 spells OTTO for player 2, and any other move lets them spell it
+    let mut pos = toot_otto_row("OTT---");
+    assert_eq!(pos.clone().play((4, 'O')), Status::Lost);
+    assert_eq!(bot::tactical_moves(&mut pos), vec![(4, 'T')]);
+}
+
+#[test]
+fn win_length_follows_board() {
+    // connect 3 on a 5x4 board: player 1 wins with a third piece in the bottom row
+    let mut pos = sized_position(5, 4, 3, &[1, 1, 2, 2]);
+    assert_eq!(pos.play(drop(&pos, 3)), Status::Won);
+    // connect 5 on an 8x7 board: four in a row is not enough
+    let mut pos = sized_position(8, 7, 5, &[1, 1, 2, 2, 3, 3]);
+    assert_eq!(pos.play(drop(&pos, 4)), Status::Ongoing);
+    assert_eq!(pos.play(drop(&pos, 4)), Status::Ongoing);
+    assert_eq!(pos.play(drop(&pos, 5)), Status::Won);
+}
@@ -0,0 +1,150 @@
+//! Tests for boards whose left and right edges join, run natively.
+
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use wasm_project3::bot::{self, BotConfig, Position, Status};
+use wasm_project3::evaluate;
+use wasm_project3::notation::{self, Game};
+use wasm_project3::rules::{Connect4, GameState, Rules};
+use wasm_project3::Board;
+
+// Connect4 position on a wrapped 6x7 board after the given columns, player 1 moving first
+fn cylinder(cols: &[usize]) -> GameState<Connect4> {
+    let mut pos = GameState::new(Connect4, Board::cylinder(6, 7, 4));
+    for &col in cols {
+        assert_eq!(pos.play((col, pos.to_move)), Status::Ongoing);
+    }
+    pos
+}
+
+#[test]
+fn lines_wrap_round_the_sides() {
+    let board = Board::cylinder(6, 7, 4);
+    assert_eq!(board.space_at(3, 0), Some((3, 7)));
+    assert_eq!(board.space_at(3, 15), Some((3, 1)));
+    assert_eq!(board.space_at(0, 3), None);
+    assert_eq!(Board::with_size(6, 7, 4).space_at(3, 8), None);
+
+    // a row goes once round from where it starts
+    assert_eq!(board.row_spaces(2), (1..=7).map(|col| (2, col)).collect::<Vec<_>>());
+    assert_eq!(board.line_spaces(2, 6, (0, 1)), vec![(2, 6), (2, 7), (2, 1), (2, 2), (2, 3), (2, 4), (2, 5)]);
+    // diagonals run from the bottom to the top row, crossing the side edge on the way
+    assert_eq!(board.right_diagonal_spaces(4, 1), vec![(6, 6), (5, 7), (4, 1), (3, 2), (2, 3), (1, 4)]);
+    assert_eq!(board.left_diagonal_spaces(4, 1), vec![(1, 5), (2, 6), (3, 7), (4, 1), (5, 2), (6, 3)]);
+
+    // on boards of every size a diagonal has a space in every row and stays on its slope
+    for num_rows in 1..=9 {
+        for num_cols in 1..=9 {
+            let board = Board::cylinder(num_rows, num_cols, 2);
+            for row in 1..=num_rows {
+                for col in 1..=num_cols {
+                    for spaces in [board.right_diagonal_spaces(row, col), board.left_diagonal_spaces(row, col)] {
+                        assert_eq!(spaces.len(), num_rows);
+                        assert!(spaces.contains(&(row, col)));
+                        assert!(spaces.windows(2).all(|pair| pair[0].1 % num_cols + 1 == pair[1].1));
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn ids_are_collected_across_the_edge() {
+    // player 1 in columns 7 and 1, player 2 on top of column 7 and in column 2
+    let pos = cylinder(&[7, 7, 1, 2]);
+    assert_eq!(pos.board.get_ids_in_row(6), vec![1, 2, 0, 0, 0, 0, 1]);
+    assert_eq!(pos.board.get_ids_in_right_diagonal(5, 7), vec![0, 2, 0, 0, 0, 0]);
+    assert_eq!(pos.board.get_ids_in_left_diagonal(5, 7), vec![0, 0, 0, 0, 2, 1]);
+}
+
+#[test]
+fn lines_across_the_edge_win() {
+    // player 1 along the bottom row in columns 6, 7 and 1, player 2 stacking on column 4
+    let mut pos = cylinder(&[6, 4, 7, 4, 1, 4]);
+    let mut flat = GameState::new(Connect4, Board::with_size(6, 7, 4));
+    for &col in &[6, 4, 7, 4, 1, 4] {
+        flat.play((col, flat.to_move));
+    }
+    assert_eq!(flat.play((2, 1)), Status::Ongoing);
+    assert_eq!(pos.play((2, 1)), Status::Won);
+    let lines = evaluate::lines_through_C4(&pos.board, 6, 2);
+    assert_eq!(lines.len(), 1);
+    assert_eq!(lines[0].spaces, vec![(6, 6), (6, 7), (6, 1), (6, 2)]);
+
+    // a diagonal climbing from column 6 over the edge to column 2, on top of player 2's pieces
+    let mut board = Board::cylinder(6, 7, 4);
+    for &(col, player_id) in &[(6, 1), (7, 2), (7, 1), (1, 2), (1, 2), (1, 1), (2, 2), (2, 2), (2, 2)] {
+        Connect4.drop_piece(&mut board, col, player_id, player_id);
+    }
+    let mut pos = GameState::new(Connect4, board);
+    assert_eq!(pos.play((2, 1)), Status::Won);
+    let lines = evaluate::lines_through_C4(&pos.board, 3, 2);
+    assert_eq!(lines.len(), 1);
+    assert_eq!(lines[0].direction, (-1, 1));
+    assert_eq!(lines[0].spaces, vec![(6, 6), (5, 7), (4, 1), (3, 2)]);
+}
+
+#[test]
+fn rows_are_only_walked_once_round() {
+    // on a board narrower than the win length a row can never be won, even going round it
+    let mut board = Board::cylinder(2, 3, 4);
+    for col in 1..=3 {
+        Connect4.drop_piece(&mut board, col, 1, 1);
+    }
+    assert!(!evaluate::is_win_at_C4(&board, 2, 3, 1));
+    assert!(evaluate::windows(&board, 4).is_empty());
+    // a row owned all the way round is one line with every space in it once
+    let mut board = Board::cylinder(6, 4, 4);
+    for col in 1..=4 {
+        Connect4.drop_piece(&mut board, col, 1, 1);
+    }
+    let lines = evaluate::lines_through_C4(&board, 6, 3);
+    assert_eq!(lines.len(), 1);
+    assert_eq!(lines[0].spaces.len(), 4);
+    // and one window, not one for every space it could start from
+    let in_row = |windows: Vec<Vec<(usize, usize)>>| windows.into_iter().filter(|window| window.iter().all(|&(row, _)| row == 6)).count();
+    assert_eq!(in_row(evaluate::windows(&board, 4)), 1);
+    assert_eq!(in_row(evaluate::windows_through(&board, 4, 6, 3)), 1);
+    assert_eq!(in_row(evaluate::windows(&Board::cylinder(6, 5, 4), 4)), 5);
+}
+
+#[test]
+fn bots_see_lines_across_the_edge() {
+    // player 1 has 7 and 1 on the bottom row, so 6 and 2 make an open three across the edge
+    let pos = cylinder(&[7, 4, 1, 4]);
+    let flat_board = {
+        let mut board = pos.board.clone();
+        board.set_wraps(false);
+        board
+    };
+    assert!(evaluate::evaluate_board_C4(&pos.board, 1) > evaluate::evaluate_board_C4(&flat_board, 1));
+
+    // player 1 has 7, 1 and 2 down, player 2 can only stop the line across the edge in column 6
+    let mut pos = cylinder(&[7, 3, 1, 3, 2]);
+    let mut rng = ChaCha8Rng::seed_from_u64(5);
+    let config = BotConfig{node_budget: 0, depth: 3, ..BotConfig::hard()};
+    assert_eq!(bot::choose_move(&mut pos, &config, &mut rng), Some((6, 2)));
+    // and if they do not, player 1 takes the win
+    pos.play((4, 2));
+    assert_eq!(bot::choose_move(&mut pos, &config, &mut rng), Some((6, 1)));
+}
+
+#[test]
+fn position_string_and_json_keep_the_wrap() {
+    let pos = cylinder(&[7, 1]);
+    let position = notation::to_position_string(&Game::from(pos));
+    assert_eq!(position, "connect4 6x7c 4 -------/-------/-------/-------/-------/2-----1 1");
+    match notation::from_position_string(&position) {
+        Ok(Game::Connect4(game)) => assert!(game.board.wraps()),
+        other => panic!("expected a Connect4 game, got {:?}", other),
+    }
+
+    let json = serde_json::to_string(&Board::cylinder(4, 5, 4)).unwrap();
+    assert!(json.ends_with(r#""num_rows":4,"num_cols":5,"win_len":4,"wraps":true}"#), "{}", json);
+    // boards saved before boards could wrap are flat
+    let flat = json.replace(r#","wraps":true"#, "");
+    let board: Board = serde_json::from_str(&flat).unwrap();
+    assert!(!board.wraps());
+    assert!(Board::cylinder(4, 5, 4).cleared().wraps());
+}
@@ -0,0 +1,41 @@
+//! Tests for the Toot-Otto window scoring, run natively.
+
+use wasm_project3::evaluate::{self, WIN_SCORE};
+use wasm_project3::rules::{Rules, TootOtto};
+use wasm_project3::Board;
+
+// One row board with the given letters dropped left to right, '-' leaving the space empty
+fn row(letters: &str) -> Board {
+    let mut board = Board::with_size(1, letters.len(), 4);
+    for (i, letter) in letters.chars().enumerate() {
+        if letter != '-' {
+            TootOtto::default().drop_piece(&mut board, i + 1, 1, letter);
+        }
+    }
+    board
+}
+
+#[test]
+fn spelled_word_scores_win_score() {
+    assert_eq!(evaluate::evaluate_board_TO(&row("TOOT"), "TOOT"), WIN_SCORE);
+    assert_eq!(evaluate::evaluate_board_TO(&row("TOOT"), "OTTO"), -WIN_SCORE);
+    assert_eq!(evaluate::evaluate_board_TO(&row("OTTO"), "OTTO"), WIN_SCORE);
+}
+
+#[test]
+fn open_window_scores_above_blocked_one() {
+    let open = evaluate::evaluate_board_TO(&row("TOO-"), "TOOT");
+    let blocked = evaluate::evaluate_board_TO(&row("TOOO"), "TOOT");
+    assert!(open > blocked, "open {} blocked {}", open, blocked);
+    assert_eq!(blocked, 0);
+    // more letters in place count for more
+    assert!(evaluate::evaluate_board_TO(&row("TO--"), "TOOT") > evaluate::evaluate_board_TO(&row("T---"), "TOOT"));
+    assert!(open > evaluate::evaluate_board_TO(&row("TO--"), "TOOT"));
+}
+
+#[test]
+fn opponent_threat_weighs_double() {
+    let own = evaluate::evaluate_board_TO(&row("OTT-"), "OTTO");
+    assert!(own > 0);
+    assert_eq!(evaluate::evaluate_board_TO(&row("OTT-"), "TOOT"), -2 * own);
+}
@@ -0,0 +1,149 @@
+//! Tests for undo and redo of the move history, run natively.
+
+use wasm_project3::history::{History, PlayedGame};
+use wasm_project3::notation::RecordResult;
+use wasm_project3::rules::{Connect4, GameState, PopOut, PopOutMove, Power, PowerUp, Rules};
+use wasm_project3::Board;
+
+// History of the given columns, alternating human and bot moves when against a bot
+fn history(cols: &[usize], against_bot: bool) -> History<usize> {
+    let mut history = History::new();
+    for (i, &col) in cols.iter().enumerate() {
+        history.record(col, against_bot && i % 2 == 1, false);
+    }
+    history
+}
+
+#[test]
+fn undo_takes_back_bot_reply_and_human_move() {
+    let mut history = history(&[4, 3, 5, 2], true);
+    assert_eq!(history.undo(), vec![2, 5]);
+    assert_eq!(history.moves(), vec![4, 3]);
+    assert_eq!(history.redo(), vec![5, 2]);
+    assert_eq!(history.moves(), vec![4, 3, 5, 2]);
+    assert!(!history.can_redo());
+}
+
+#[test]
+fn undo_takes_back_one_move_between_humans() {
+    let mut history = history(&[4, 3, 5], false);
+    assert_eq!(history.undo(), vec![5]);
+    assert_eq!(history.undo(), vec![3]);
+    assert_eq!(history.redo(), vec![3]);
+    assert_eq!(history.moves(), vec![4, 3]);
+    history.undo();
+    history.undo();
+    assert_eq!(history.undo(), Vec::<usize>::new());
+    assert!(!history.can_undo());
+}
+
+#[test]
+fn new_move_clears_redo() {
+    let mut history = history(&[4, 3], true);
+    history.undo();
+    history.record(1, false, false);
+    assert!(!history.can_redo());
+    assert_eq!(history.redo(), Vec::<usize>::new());
+    assert_eq!(history.moves(), vec![1]);
+}
+
+#[test]
+fn undo_clears_game_over() {
+    let mut history = history(&[4, 3], true);
+    history.record(4, false, true);
+    assert!(history.is_game_over());
+    assert_eq!(history.undo(), vec![4]);
+    assert!(!history.is_game_over());
+    history.redo();
+    assert!(history.is_game_over());
+}
+
+// Connect4 game where a human plays the even moves and a bot answers with the odd ones
+fn against_bot(cols: &[usize]) -> PlayedGame<Connect4> {
+    let mut game = PlayedGame::new(GameState::new(Connect4, Board::with_size(6, 7, 4)));
+    for (i, &col) in cols.iter().enumerate() {
+        let player_id = 1 + i % 2;
+        game.play(col, player_id, player_id, i % 2 == 1);
+    }
+    game
+}
+
+#[test]
+fn game_undo_takes_back_human_move_and_bot_reply() {
+    let mut game = against_bot(&[4, 3, 5, 3]);
+    let taken_back: Vec<(usize, usize)> = game.undo().iter().map(|mv| (mv.row, mv.col)).collect();
+    assert_eq!(taken_back, vec![(5, 3), (6, 5)]);
+    assert_eq!(game.state.board.player_at(5, 3), None);
+    assert_eq!(game.state.board.player_at(6, 5), None);
+    assert_eq!(game.state.to_move, 1);
+    assert_eq!(game.moves(), vec![(4, 1), (3, 2)]);
+
+    // redo puts the same pieces back
+    game.redo();
+    assert_eq!(game.state.board.player_at(6, 5), Some(1));
+    assert_eq!(game.state.board.player_at(5, 3), Some(2));
+    assert_eq!(game.state.to_move, 1);
+    assert_eq!(game.moves().len(), 4);
+}
+
+#[test]
+fn game_undo_clears_game_over() {
+    // player 1 wins along the bottom row
+    let mut game = against_bot(&[1, 1, 2, 2, 3, 3, 4]);
+    assert!(game.history().is_game_over());
+    assert_eq!(game.result(), RecordResult::Win(1));
+    game.undo();
+    assert!(!game.history().is_game_over());
+    assert_eq!(game.result(), RecordResult::Unfinished);
+    assert_eq!(game.state.to_move, 1);
+    game.redo();
+    assert!(game.history().is_game_over());
+    assert_eq!(game.result(), RecordResult::Win(1));
+}
+
+#[test]
+fn game_only_records_moves_that_were_played() {
+    let mut game = against_bot(&[]);
+    for _ in 0..6 {
+        game.play(1, 1, 1, false);
+    }
+    assert_eq!(game.play(1, 2, 2, false), None);
+    // a Connect4 player only has pieces of their own
+    assert_eq!(game.play(2, 2, 1, false), None);
+    assert_eq!(game.moves().len(), 6);
+}
+
+#[test]
+fn game_undo_and_redo_a_pop() {
+    let mut game = PlayedGame::new(GameState::new(PopOut, Board::with_size(6, 7, 4)));
+    game.play(4, 1, PopOutMove::Drop, false);
+    game.play(4, 2, PopOutMove::Drop, false);
+    assert_eq!(game.play(4, 1, PopOutMove::Pop, false), Some(6));
+    assert_eq!(game.state.board.player_at(6, 4), Some(2));
+
+    game.undo();
+    assert_eq!(game.state.board.player_at(6, 4), Some(1));
+    assert_eq!(game.state.board.player_at(5, 4), Some(2));
+    assert!(!PopOut.fills_one_space(PopOutMove::Pop));
+    game.redo();
+    assert_eq!(game.state.board.player_at(6, 4), Some(2));
+    assert_eq!(game.state.board.player_at(5, 4), None);
+}
+
+#[test]
+fn game_undo_and_redo_a_power_piece() {
+    let mut game = PlayedGame::new(GameState::new(PowerUp::default(), Board::with_size(6, 7, 4)));
+    for &player_id in &[1, 2, 1] {
+        game.play(3, player_id, Power::Disc, false);
+    }
+    assert_eq!(game.play(3, 2, Power::Anvil, false), Some(6));
+    assert_eq!(game.state.rules.powers_left(2), vec![Power::Bomb, Power::Wall]);
+
+    game.undo();
+    let col: Vec<Option<usize>> = (4..=6).map(|row| game.state.board.player_at(row, 3)).collect();
+    assert_eq!(col, vec![Some(1), Some(2), Some(1)]);
+    assert_eq!(game.state.rules.powers_left(2), Power::POWERS.to_vec());
+    game.redo();
+    assert_eq!(game.state.board.player_at(6, 3), Some(2));
+    assert_eq!(game.state.board.player_at(5, 3), None);
+}
@@ -0,0 +1,94 @@
+//! Tests for the Monte Carlo tree search engine, run natively on Connect4 positions.
+
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use wasm_project3::bot::{self, BotConfig, Position, Status};
+use wasm_project3::mcts;
+use wasm_project3::rules::{Connect4, GameState};
+use wasm_project3::Board;
+
+// Connect4 position after the given columns were played, player 1 moving first
+fn position(cols: &[usize]) -> GameState<Connect4> {
+    let mut pos = GameState::new(Connect4, Board::with_size(6, 7, 4));
+    for &col in cols {
+        pos.play((col, pos.to_move));
+    }
+    pos
+}
+
+// Monte Carlo bot running the given iterations with the tactical layer and heuristic playouts
+// off, so only the search itself picks
+fn uct(iterations: u32) -> BotConfig {
+    BotConfig{block_threats: false, heuristic_playouts: false, ..BotConfig::monte_carlo(iterations, 0.0)}
+}
+
+// A position that counts the moves played on it
+struct Counting<P> {
+    pos: P,
+    plays: u32,
+}
+
+impl<P: Position> Position for Counting<P> {
+    type Move = P::Move;
+    fn legal_moves(&self) -> Vec<P::Move> {
+        self.pos.legal_moves()
+    }
+    fn play(&mut self, mv: P::Move) -> Status {
+        self.plays += 1;
+        self.pos.play(mv)
+    }
+    fn undo(&mut self, mv: P::Move) {
+        self.pos.undo(mv)
+    }
+    fn evaluate(&self) -> i32 {
+        self.pos.evaluate()
+    }
+}
+
+#[test]
+fn uct_takes_immediate_win() {
+    // player 1 has columns 1-3 on the bottom row
+    let mut pos = position(&[1, 1, 2, 2, 3, 3]);
+    let mut rng = ChaCha8Rng::seed_from_u64(0);
+    assert_eq!(bot::choose_move(&mut pos, &uct(500), &mut rng), Some((4, 1)));
+}
+
+#[test]
+fn uct_blocks_one_move_loss() {
+    // player 1 threatens column 4, player 2 is to move
+    let mut pos = position(&[1, 7, 2, 7, 3]);
+    let mut rng = ChaCha8Rng::seed_from_u64(0);
+    assert_eq!(bot::choose_move(&mut pos, &uct(2000), &mut rng), Some((4, 2)));
+}
+
+#[test]
+fn same_seed_gives_same_move() {
+    let mut pos = position(&[4, 4, 3]);
+    let moves = pos.legal_moves();
+    let search = |pos: &mut GameState<Connect4>, seed| mcts::search(pos, &moves, &uct(300), &mut ChaCha8Rng::seed_from_u64(seed));
+    let visits = search(&mut pos, 7);
+    assert_eq!(search(&mut pos, 7), visits);
+    let pick = |seed| bot::choose_move(&mut position(&[4, 4, 3]), &uct(300), &mut ChaCha8Rng::seed_from_u64(seed));
+    assert_eq!(pick(7), pick(7));
+}
+
+#[test]
+fn search_runs_the_configured_iterations_and_playouts() {
+    let moves = position(&[]).legal_moves();
+    let mut rng = ChaCha8Rng::seed_from_u64(1);
+    for iterations in [1, 50, 400] {
+        let visited = mcts::search(&mut position(&[]), &moves, &uct(iterations), &mut rng);
+        assert_eq!(visited.iter().map(|&(_, visits)| visits).sum::<u32>(), iterations);
+        assert_eq!(visited.len(), moves.len());
+    }
+
+    // heuristic playouts try every move to find the tactical ones, random playouts do not
+    let mut plays = vec![];
+    for heuristic_playouts in [false, true] {
+        let config = BotConfig{heuristic_playouts, ..uct(20)};
+        let mut counting = Counting{pos: position(&[]), plays: 0};
+        mcts::search(&mut counting, &moves, &config, &mut ChaCha8Rng::seed_from_u64(1));
+        plays.push(counting.plays);
+    }
+    assert!(plays[1] > 3 * plays[0], "{:?}", plays);
+}
@@ -0,0 +1,136 @@
+//! Tests for reading and writing games as text, run natively.
+
+use wasm_project3::bot::Position;
+use wasm_project3::notation;
+use wasm_project3::Board;
+
+fn board() -> Board {
+    Board::with_size(6, 7, 4)
+}
+
+#[test]
+fn connect4_notation_round_trips() {
+    let game = notation::parse_C4("4453323", board()).unwrap();
+    assert_eq!(game.board.player_at(6, 4), Some(1));
+    assert_eq!(game.board.player_at(5, 4), Some(2));
+    assert_eq!(game.board.player_at(4, 3), Some(1));
+    assert_eq!(game.to_move, 2);
+
+    let cols = notation::parse_columns_C4("44 53 323").unwrap();
+    assert_eq!(cols, vec![4, 4, 5, 3, 3, 2, 3]);
+    assert_eq!(notation::write_C4(&cols).unwrap(), "4453323");
+}
+
+#[test]
+fn connect4_notation_rejects_illegal_games() {
+    assert_eq!(notation::parse_C4("44x3", board()).unwrap_err(), "move 3 is 'x', expected a column from 1 to 9");
+    assert_eq!(notation::parse_C4("440", board()).unwrap_err(), "move 3 is '0', expected a column from 1 to 9");
+    assert_eq!(notation::parse_C4("48", board()).unwrap_err(), "move 2 is in column 8, the board has 7 columns");
+    assert_eq!(notation::parse_C4("1111111", board()).unwrap_err(), "move 7 is in column 1, which is full");
+    // player 1 has four in column 1 after move 7
+    assert_eq!(notation::parse_C4("12121213", board()).unwrap_err(), "move 8 is played after the game ended");
+    assert!(notation::write_C4(&[4, 10]).is_err());
+}
+
+#[test]
+fn connect4_notation_of_empty_game() {
+    let game = notation::parse_C4("", board()).unwrap();
+    assert_eq!(game.legal_moves().len(), 7);
+    assert_eq!(notation::write_C4(&[]).unwrap(), "");
+}
+
+// player 1 spells OTTO along the bottom row with the seventh move
+const OTTO_GAME: &str = "[Player1 \"alice\"]\n[Player2 \"Hard Bot\"]\n[Toot \"1\"]\n[Result \"0-1\"]\n1O 1T 2T 2T 3T 3T 4O";
+
+#[test]
+fn toot_otto_notation_round_trips() {
+    let record = notation::parse_TO(OTTO_GAME).unwrap();
+    assert_eq!(record.players, ["alice".to_string(), "Hard Bot".to_string()]);
+    assert_eq!(record.toot_player, 1);
+    assert_eq!(record.result, notation::RecordResult::Win(2));
+    assert_eq!(record.moves[..2], [(1, 'O'), (1, 'T')]);
+    assert_eq!(notation::write_TO(&record), OTTO_GAME);
+
+    let game = record.game(Board::with_size(4, 6, 4)).unwrap();
+    assert_eq!(game.board.letter_at(4, 4), Some('O'));
+    assert_eq!(notation::result_of(&game, record.moves.last()), notation::RecordResult::Win(2));
+}
+
+#[test]
+fn toot_otto_player_names_are_escaped() {
+    let mut record = notation::parse_TO(OTTO_GAME).unwrap();
+    for name in ["the \"Hard\" Bot", "[bot]", "back\\slash\\", "two\nlines", "\\\"]"] {
+        record.players = [name.to_string(), "bob".to_string()];
+        let text = notation::write_TO(&record);
+        assert_eq!(notation::parse_TO(&text).unwrap(), record, "{}", text);
+    }
+    record.players[0] = "the \"Hard\" Bot]".to_string();
+    assert!(notation::write_TO(&record).starts_with("[Player1 \"the \\\"Hard\\\" Bot\\]\"]\n"));
+    assert!(notation::parse_TO("[Player1 \"a\\b\"]").is_err());
+}
+
+#[test]
+fn toot_otto_notation_defaults_missing_headers() {
+    let record = notation::parse_TO("3t 4o\n3O").unwrap();
+    assert_eq!(record.players, ["Player 1".to_string(), "Player 2".to_string()]);
+    assert_eq!(record.result, notation::RecordResult::Unfinished);
+    assert_eq!(record.moves, vec![(3, 'T'), (4, 'O'), (3, 'O')]);
+}
+
+#[test]
+fn toot_otto_notation_rejects_bad_games() {
+    let board = || Board::with_size(4, 6, 4);
+    assert_eq!(notation::parse_TO("3T 4X").unwrap_err(), "move 2 is '4X', expected a column and a letter like 3T");
+    assert_eq!(notation::parse_TO("[Toot \"3\"]").unwrap_err(), "Toot must be player 1 or 2, got '3'");
+    assert_eq!(notation::parse_TO("[Event \"x\"]").unwrap_err(), "unknown header 'Event'");
+    assert!(notation::parse_TO("[Toot 1]").is_err());
+    let record = notation::parse_TO("7T").unwrap();
+    assert_eq!(record.game(board()).unwrap_err(), "move 1 is in column 7, the board has 6 columns");
+    // the same game, but with player 2 spelling TOOT the OTTO along the bottom wins for player 1
+    let record = notation::parse_TO(&OTTO_GAME.replace("[Toot \"1\"]", "[Toot \"2\"]")).unwrap();
+    assert_eq!(record.game(board()).unwrap_err(), "the moves end the game 1-0, but the result is 0-1");
+}
+
+#[test]
+fn position_strings_round_trip() {
+    let game = notation::parse_C4("4453", board()).unwrap();
+    let position = notation::to_position_string(&notation::Game::Connect4(game));
+    assert_eq!(position, "connect4 6x7 4 -------/-------/-------/-------/---2---/--211-- 1");
+    let loaded = notation::from_position_string(&position).unwrap();
+    assert_eq!(loaded.to_move(), 1);
+    assert_eq!(loaded.board().player_at(5, 4), Some(2));
+    assert_eq!(notation::to_position_string(&loaded), position);
+
+    let record = notation::parse_TO("[Toot \"2\"]\n2T 3o 4T").unwrap();
+    let game = record.game(Board::with_size(4, 6, 4)).unwrap();
+    let position = notation::to_position_string(&notation::Game::TootOtto(game));
+    assert_eq!(position, "toot-otto 4x6 4 ------/------/------/-ToT-- 2 4T6O,6T5O 2");
+    let loaded = notation::from_position_string(&position).unwrap();
+    assert_eq!(notation::to_position_string(&loaded), position);
+    match loaded {
+        notation::Game::TootOtto(game) => {
+            assert_eq!(game.rules.word(1), "OTTO");
+            assert_eq!(game.board.player_at(4, 3), Some(2));
+            assert_eq!(game.board.letter_at(4, 3), Some('O'));
+        },
+        _ => panic!("loaded a Toot-Otto position as another game"),
+    }
+}
+
+#[test]
+fn position_strings_reject_impossible_positions() {
+    let error = |position: &str| notation::from_position_string(position).unwrap_err();
+    assert_eq!(error("chess 6x7 4 - 1"), "the position must start with the game, connect4, toot-otto or popout");
+    assert_eq!(error("connect4 6x7 4 ---/--- 1"), "expected 6 rows, got 2");
+    assert_eq!(error("connect4 2x4 4 ----/--- 1"), "row 2 has 3 spaces, expected 4");
+    assert_eq!(error("connect4 2x4 4 -1--/---- 2"), "the piece at R1C2 is floating above an empty space");
+    assert_eq!(error("connect4 2x4 4 ----/-3-- 2"), "unknown space '3' at R2C2");
+    assert_eq!(error("connect4 2x4 4 ----/-1-- 1"), "player 2 is to move with 1 and 0 pieces on the board, not player 1");
+    assert_eq!(error("toot-otto 2x4 4 ----/-T-- 2 2T2O,2T2O 1"), "the letters left are 1T2O,2T2O for the pieces on the board, not 2T2O,2T2O");
+    assert_eq!(error("toot-otto 2x4 4 ----/-1-- 2 2T2O,2T2O 1"), "unknown space '1' at R2C2");
+    // the size is checked before a board is made for it
+    assert_eq!(error("connect4 1000000x1000000 4 - 1"), "board size '1000000x1000000' is too big, boards have at most 20 rows and columns");
+    assert_eq!(error("connect4 21x7 4 - 1"), "board size '21x7' is too big, boards have at most 20 rows and columns");
+    assert_eq!(error("connect4 20x20 4 ---- 1"), "expected 20 rows, got 1");
+    assert_eq!(error("connect4 2x3 4 ---/--- 1"), "win length 4 does not fit on a 2x3 board");
+}
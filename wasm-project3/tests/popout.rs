@@ -0,0 +1,110 @@
+//! Tests for the Pop Out rules and bots, run natively.
+
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use wasm_project3::bot::{self, BotConfig, Position, Status};
+use wasm_project3::notation::{self, Game};
+use wasm_project3::rules::{GameState, OutcomeC4, PopOut, PopOutMove, Rules};
+use wasm_project3::Board;
+
+// Pop Out position with the given player's pieces dropped into the given columns, player 1 to move
+fn popout(drops: &[(usize, usize)]) -> GameState<PopOut> {
+    let mut board = Board::with_size(6, 7, 4);
+    for &(col, player_id) in drops {
+        PopOut.drop_piece(&mut board, col, player_id, PopOutMove::Drop);
+    }
+    GameState::new(PopOut, board)
+}
+
+// player 2 under player 1 in columns 1 to 3, and 1, 2, 1 up column 4. Popping column 4 drops
+// player 2's piece into the bottom row and player 1's into the row above, a line for each
+fn both_lines_after_pop() -> GameState<PopOut> {
+    popout(&[(1, 2), (1, 1), (2, 2), (2, 1), (3, 2), (3, 1), (4, 1), (4, 2), (4, 1)])
+}
+
+#[test]
+fn pop_shifts_column_down_and_undo_lifts_it_back() {
+    let mut pos = popout(&[(3, 1), (3, 2), (3, 1)]);
+    assert!(pos.legal_moves().contains(&(3, PopOutMove::Pop)));
+    assert_eq!(pos.play((3, PopOutMove::Pop)), Status::Ongoing);
+    assert_eq!(pos.board.player_at(6, 3), Some(2));
+    assert_eq!(pos.board.player_at(5, 3), Some(1));
+    assert_eq!(pos.board.player_at(4, 3), None);
+    assert_eq!(pos.to_move, 2);
+
+    pos.undo((3, PopOutMove::Pop));
+    assert_eq!(pos.to_move, 1);
+    let ids: Vec<Option<usize>> = (4..=6).map(|row| pos.board.player_at(row, 3)).collect();
+    assert_eq!(ids, vec![Some(1), Some(2), Some(1)]);
+}
+
+#[test]
+fn only_own_bottom_pieces_can_be_popped() {
+    let pos = popout(&[(1, 1), (2, 2), (2, 1)]);
+    let pops: Vec<usize> = pos.legal_moves().into_iter()
+        .filter(|&(_, mv)| mv == PopOutMove::Pop)
+        .map(|(col, _)| col)
+        .collect();
+    assert_eq!(pops, vec![1]);
+    let mut board = pos.board.clone();
+    assert_eq!(PopOut.drop_piece(&mut board, 2, 1, PopOutMove::Pop), None);
+    assert_eq!(PopOut.pieces(&pos.board, 2), vec![PopOutMove::Drop, PopOutMove::Pop]);
+    assert_eq!(PopOut.pieces(&popout(&[]).board, 1), vec![PopOutMove::Drop]);
+}
+
+#[test]
+fn pop_lining_up_both_players_wins_for_popper() {
+    let mut pos = both_lines_after_pop();
+    assert_eq!(pos.play((4, PopOutMove::Pop)), Status::Won);
+    assert_eq!(PopOut.outcome(&pos.board, 6, 4, 1), Some(OutcomeC4::Win(1)));
+    assert_eq!(notation::result_of(&pos, Some(&(4, PopOutMove::Pop))), notation::RecordResult::Win(1));
+
+    // a pop that only lines up the opponent's pieces loses
+    let mut pos = popout(&[(1, 2), (2, 2), (3, 2), (4, 1), (4, 2)]);
+    assert_eq!(pos.play((4, PopOutMove::Pop)), Status::Lost);
+}
+
+#[test]
+fn full_board_is_a_draw_only_when_next_player_cannot_pop() {
+    // no line of 4 fits on the board
+    let mut board = Board::with_size(2, 3, 4);
+    for (col, player_id) in [(1, 1), (2, 1), (3, 1), (1, 2), (2, 2), (3, 2)] {
+        PopOut.drop_piece(&mut board, col, player_id, PopOutMove::Drop);
+    }
+    assert_eq!(PopOut.outcome(&board, 1, 3, 2), None);
+    assert_eq!(PopOut.outcome(&board, 1, 3, 1), Some(OutcomeC4::Draw));
+}
+
+#[test]
+fn bots_play_legal_moves_and_find_winning_pop() {
+    let mut rng = ChaCha8Rng::seed_from_u64(3);
+    let config = BotConfig{node_budget: 0, depth: 3, ..BotConfig::hard()};
+    assert_eq!(bot::choose_move(&mut both_lines_after_pop(), &config, &mut rng), Some((4, PopOutMove::Pop)));
+
+    // a few moves of the Monte Carlo bot, its playouts of long Pop Out games are slow
+    for (config, num_moves) in [(BotConfig::easy(), 20), (BotConfig::monte_carlo(20, 0.0), 3)] {
+        let mut pos = popout(&[]);
+        for _ in 0..num_moves {
+            let mv = match bot::choose_move(&mut pos, &config, &mut rng) {
+                Some(mv) => mv,
+                None => break,
+            };
+            assert!(pos.legal_moves().contains(&mv), "{:?} is not legal", mv);
+            if pos.play(mv) != Status::Ongoing {
+                break;
+            }
+        }
+    }
+}
+
+#[test]
+fn position_string_allows_either_player_to_move() {
+    let mut pos = popout(&[(4, 1), (4, 2), (5, 1)]);
+    pos.play((4, PopOutMove::Pop));
+    let position = notation::to_position_string(&Game::from(pos));
+    assert_eq!(position, "popout 6x7 4 -------/-------/-------/-------/-------/---21-- 2");
+    match notation::from_position_string(&position) {
+        Ok(Game::PopOut(game)) => assert_eq!(game.to_move, 2),
+        other => panic!("expected a Pop Out game, got {:?}", other),
+    }
+}
@@ -0,0 +1,63 @@
+//! Tests for finding "win in N" puzzles, run natively.
+
+use wasm_project3::js::{Piece, Placed};
+use wasm_project3::notation::{self, Game};
+use wasm_project3::puzzle;
+use wasm_project3::rules::GameMode;
+use wasm_project3::Board;
+
+fn board() -> Board {
+    Board::with_size(6, 7, 4)
+}
+
+#[test]
+fn open_three_is_win_in_two() {
+    // player 1 has columns 4 and 5 on the bottom row, player 2 sits on column 7, so only
+    // column 3 makes three in a row with both ends open
+    let mut game = notation::parse_C4("4457", board()).unwrap();
+    assert_eq!(puzzle::win_in(&mut game, 3), Some(2));
+    assert_eq!(puzzle::winning_moves(&mut game, 2), vec![(3, 1)]);
+
+    let puzzle = puzzle::puzzle_at(&game, 3).unwrap();
+    assert_eq!(puzzle.game, "connect4");
+    assert_eq!(puzzle.win_in, 2);
+    assert_eq!(puzzle.position, notation::to_position_string(&Game::Connect4(game.clone())));
+    assert_eq!(puzzle.solution.len(), 3);
+    assert_eq!(puzzle.solution[0], Placed{row: 6, col: 3, piece: Piece::Player(1)});
+    // player 2 blocks one end and player 1 wins at the other
+    let block = puzzle.solution[1].col;
+    assert!(block == 2 || block == 6);
+    assert_eq!(puzzle.solution[2].col, 8 - block);
+}
+
+#[test]
+fn no_puzzle_without_forced_win_or_with_two_answers() {
+    let game = notation::parse_C4("", board()).unwrap();
+    assert_eq!(puzzle::puzzle_at(&game, 3), None);
+    // with column 7 free, columns 3 and 6 both open a three with two ends
+    let game = notation::parse_C4("4451", board()).unwrap();
+    assert_eq!(puzzle::puzzle_at(&game, 3), None);
+}
+
+#[test]
+fn puzzles_of_a_stored_game_are_found() {
+    let puzzles = puzzle::puzzles_in_notation(GameMode::Connect4, "445712", 3).unwrap();
+    assert_eq!(puzzles.len(), 1);
+    assert_eq!(puzzles[0].solution[0].col, 3);
+    assert!(puzzle::puzzles_in_notation(GameMode::Connect4, "1111111", 3).is_err());
+}
+
+#[test]
+fn generated_puzzles_are_forced_wins() {
+    let puzzles = puzzle::generate(GameMode::Connect4, 2, 5, 2).unwrap();
+    assert!(!puzzles.is_empty());
+    assert_eq!(puzzles, puzzle::generate(GameMode::Connect4, 2, 5, 2).unwrap());
+    for found in puzzles {
+        let mut game = match notation::from_position_string(&found.position).unwrap() {
+            Game::Connect4(game) => game,
+            _ => panic!("not a connect4 puzzle"),
+        };
+        assert_eq!(puzzle::win_in(&mut game, 2), Some(found.win_in));
+        assert_eq!(puzzle::winning_moves(&mut game, found.win_in).len(), 1);
+    }
+}
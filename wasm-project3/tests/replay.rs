@@ -0,0 +1,70 @@
+//! Tests for stepping through recorded games, run natively.
+
+use wasm_project3::bot::BotConfig;
+use wasm_project3::evaluate::WIN_SCORE;
+use wasm_project3::js::{Piece, Placed};
+use wasm_project3::notation::{self, RecordResult};
+use wasm_project3::replay::GameReplay;
+use wasm_project3::rules::Connect4;
+use wasm_project3::Board;
+
+// replay of a Connect4 game in column notation on the standard board
+fn connect4(notation: &str) -> GameReplay<Connect4> {
+    let moves = notation::moves_C4(notation).unwrap();
+    GameReplay::new(Connect4, Board::with_size(6, 7, 4), moves).unwrap()
+}
+
+fn engine() -> BotConfig {
+    BotConfig{node_budget: 0, depth: 3, ..BotConfig::medium()}
+}
+
+#[test]
+fn replay_steps_and_seeks_through_game() {
+    let mut replay = connect4("4453323");
+    assert_eq!(replay.ply(), 0);
+    assert_eq!(replay.num_plies(), 7);
+    assert_eq!(replay.last_move(), None);
+    assert!(!replay.step_back());
+
+    assert!(replay.step_forward());
+    assert!(replay.step_forward());
+    assert_eq!(replay.last_move(), Some(Placed{row: 5, col: 4, piece: Piece::Player(2)}));
+    assert_eq!(replay.to_move(), 1);
+
+    replay.seek(100);
+    assert_eq!(replay.ply(), 7);
+    assert!(!replay.step_forward());
+    assert_eq!(replay.board().player_at(4, 3), Some(1));
+
+    // seeking back gives the same board as stepping forward to the ply
+    replay.seek(3);
+    assert_eq!(replay.board().player_at(4, 3), None);
+    assert_eq!(replay.board().player_at(6, 5), Some(1));
+    assert_eq!(replay.last_move(), Some(Placed{row: 6, col: 5, piece: Piece::Player(1)}));
+    replay.seek(0);
+    assert!((1..=7).all(|col| replay.board().drop_row(col) == Some(6)));
+}
+
+#[test]
+fn replay_rejects_illegal_moves() {
+    let moves = (0..7).map(|i| (1, 1 + i % 2)).collect();
+    let err = GameReplay::new(Connect4, Board::with_size(6, 7, 4), moves).unwrap_err();
+    assert_eq!(err, "move 7 is in column 1, which is full");
+}
+
+#[test]
+fn replay_evaluates_from_player_one_view() {
+    // player 1 wins along the bottom row on the 7th move
+    let mut replay = connect4("1122334");
+    assert_eq!(replay.result(), RecordResult::Win(1));
+    replay.seek(7);
+    assert_eq!(replay.evaluation(&engine()), WIN_SCORE);
+
+    // before the winning move player 1 has a win in one
+    replay.seek(6);
+    assert!(replay.evaluation(&engine()) >= WIN_SCORE);
+    // a ply earlier player 2 is to move and can still block it
+    replay.seek(5);
+    assert_eq!(replay.to_move(), 2);
+    assert!(replay.evaluation(&engine()) < WIN_SCORE);
+}
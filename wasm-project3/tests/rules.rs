@@ -0,0 +1,184 @@
+//! Tests for the rules of the games, run natively.
+
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use wasm_project3::bot::{self, BotConfig, Position, Status};
+use wasm_project3::evaluate;
+use wasm_project3::rules::{Connect4, FiveInARow, GameMode, GameState, OutcomeTO, PopOut, PowerUp, Rules, TootOtto};
+use wasm_project3::{set_game, set_toot_player, word_of, Board};
+
+// Connect4 board with the given player's pieces dropped in the given columns
+fn connect4(drops: &[(usize, usize)]) -> Board {
+    let mut board = Board::with_size(6, 7, 4);
+    for &(col, player_id) in drops {
+        Connect4.drop_piece(&mut board, col, player_id, player_id);
+    }
+    board
+}
+
+// Toot-Otto position after the given (col, letter) moves were played, player 1 moving first and spelling TOOT
+fn toot_otto(moves: &[(usize, char)]) -> GameState<TootOtto> {
+    toot_otto_with(TootOtto::default(), moves)
+}
+
+// Same as toot_otto, with the given words for the players
+fn toot_otto_with(rules: TootOtto, moves: &[(usize, char)]) -> GameState<TootOtto> {
+    let mut pos = GameState::new(rules, Board::with_size(4, 6, 4));
+    for &mv in moves {
+        pos.play(mv);
+    }
+    pos
+}
+
+// The mode and board size of the given rules
+fn mode_and_size<R: Rules>(rules: R) -> (GameMode, (usize, usize, usize)) {
+    (rules.mode(), rules.board_size())
+}
+
+#[test]
+fn rules_report_their_mode_and_new_boards_follow_it() {
+    let rules = [
+        mode_and_size(Connect4),
+        mode_and_size(TootOtto::default()),
+        mode_and_size(PopOut),
+        mode_and_size(FiveInARow),
+        mode_and_size(PowerUp::default()),
+    ];
+    for (mode, size) in rules {
+        assert_eq!(mode.board_size(), size);
+        set_game(mode);
+        let board = Board::new();
+        assert_eq!((board.num_rows(), board.num_cols(), board.win_len()), size, "{}", mode.name());
+    }
+    set_game(GameMode::TootOtto);
+    assert_eq!(Board::new().num_cols(), 6);
+    set_game(GameMode::Connect4);
+    assert_eq!(Board::new().num_cols(), 7);
+}
+
+#[test]
+fn toot_player_can_be_switched() {
+    assert!(set_toot_player(2).is_ok());
+    assert_eq!(word_of(2), "TOOT");
+    assert_eq!(word_of(1), "OTTO");
+    assert!(set_toot_player(1).is_ok());
+    assert_eq!(word_of(1), "TOOT");
+}
+
+#[test]
+fn toot_otto_players_start_with_six_of_each_letter() {
+    let pos = toot_otto(&[]);
+    assert_eq!(pos.rules.letters_left(&pos.board, 1), (6, 6));
+    assert_eq!(pos.rules.letters_left(&pos.board, 2), (6, 6));
+    let pos = toot_otto(&[(1, 'T'), (1, 'O'), (2, 'T')]);
+    assert_eq!(pos.rules.letters_left(&pos.board, 1), (4, 6));
+    assert_eq!(pos.rules.letters_left(&pos.board, 2), (6, 5));
+}
+
+#[test]
+fn toot_otto_player_without_letter_cannot_drop_it() {
+    // player 1 drops all six T's in columns 1 and 2, player 2 answers with O's in columns 5 and 6
+    let mut moves = vec![];
+    for i in 0..6 {
+        moves.push((1 + i / 3, 'T'));
+        moves.push((5 + i / 3, 'O'));
+    }
+    let mut pos = toot_otto(&moves);
+    assert_eq!(pos.rules.letters_left(&pos.board, 1), (0, 6));
+    assert_eq!(pos.rules.pieces(&pos.board, 1), vec!['O']);
+    assert!(pos.legal_moves().iter().all(|&(_, letter)| letter == 'O'));
+
+    let mut rng = ChaCha8Rng::seed_from_u64(0);
+    for config in [BotConfig::easy(), BotConfig::hard()] {
+        let config = BotConfig{node_budget: 0, depth: 2, ..config};
+        let (_, letter) = bot::choose_move(&mut pos, &config, &mut rng).unwrap();
+        assert_eq!(letter, 'O');
+    }
+}
+
+#[test]
+fn toot_otto_win_goes_to_owner_of_word() {
+    // player 1 spells O-T-T along the bottom row while player 2 drops T's on top of them
+    let moves = [(1, 'O'), (1, 'T'), (2, 'T'), (2, 'T'), (3, 'T'), (3, 'T')];
+    let rules = TootOtto::default();
+    assert_eq!(rules.word(1), "TOOT");
+    assert_eq!(toot_otto_with(rules, &moves).play((4, 'O')), Status::Lost);
+
+    // after switching words, player 1 owns OTTO and the same move wins for them
+    let rules = TootOtto::new(2);
+    assert_eq!(rules.word(1), "OTTO");
+    assert_eq!(toot_otto_with(rules, &moves).play((4, 'O')), Status::Won);
+}
+
+#[test]
+fn toot_otto_move_spelling_both_words_ties() {
+    // columns are filled bottom up, the last T in column 2 spells OTTO along the top row
+    // and TOOT down column 2
+    let mut rules = TootOtto::default();
+    let mut board = Board::with_size(4, 6, 4);
+    for (col, letters) in [(1, "TTTO"), (2, "TOO"), (3, "TTTT"), (4, "TTTO")] {
+        for letter in letters.chars() {
+            rules.drop_piece(&mut board, col, 1, letter);
+        }
+    }
+    rules.drop_piece(&mut board, 2, 1, 'T');
+
+    let words = evaluate::words_through_TO(&board, 1, 2);
+    assert_eq!(words.len(), 2);
+    assert!(words.iter().any(|word| word.word == "OTTO" && word.spaces == vec![(1, 1), (1, 2), (1, 3), (1, 4)]));
+    assert!(words.iter().any(|word| word.word == "TOOT" && word.spaces == vec![(1, 2), (2, 2), (3, 2), (4, 2)]));
+    assert_eq!(rules.outcome(&board, 1, 2, 1), Some(OutcomeTO::Tie));
+    assert_eq!(rules.winner(OutcomeTO::Tie), None);
+}
+
+#[test]
+fn toot_otto_words_created_by_move_are_found_by_full_scan() {
+    let mut rng = ChaCha8Rng::seed_from_u64(7);
+    for _ in 0..200 {
+        let mut pos = toot_otto(&[]);
+        loop {
+            let moves = pos.legal_moves();
+            if moves.is_empty() {
+                break;
+            }
+            let before = evaluate::words_TO(&pos.board);
+            let (col, letter) = moves[rng.gen_range(0..moves.len())];
+            pos.play((col, letter));
+            let row = (1..=4).find(|&row| pos.board.letter_at(row, col).is_some()).unwrap();
+            let mut created = evaluate::words_TO(&pos.board);
+            created.retain(|word| !before.contains(word));
+            assert_eq!(created.len(), evaluate::words_through_TO(&pos.board, row, col).len());
+            assert!(created.iter().all(|word| word.spaces.contains(&(row, col))));
+            if !created.is_empty() {
+                break;
+            }
+        }
+    }
+}
+
+#[test]
+fn connect4_winning_line_covers_whole_run() {
+    // player 1 fills the gap in the middle of 1 1 _ 1 1 on the bottom row
+    let board = connect4(&[(1, 1), (2, 1), (4, 1), (5, 1), (3, 1)]);
+    let lines = evaluate::lines_through_C4(&board, 6, 3);
+    assert_eq!(lines.len(), 1);
+    assert_eq!(evaluate::direction_name(lines[0].direction), "horizontal");
+    assert_eq!(lines[0].spaces, vec![(6, 1), (6, 2), (6, 3), (6, 4), (6, 5)]);
+    assert!(evaluate::lines_through_C4(&board, 6, 7).is_empty());
+}
+
+#[test]
+fn connect4_finds_simultaneous_lines() {
+    // the last piece in column 4 completes the bottom row and the right diagonal
+    let board = connect4(&[
+        (1, 1), (2, 1), (3, 1),
+        (5, 2), (5, 1),
+        (6, 2), (6, 2), (6, 1),
+        (7, 2), (7, 2), (7, 2), (7, 1),
+        (4, 1),
+    ]);
+    let lines = evaluate::lines_through_C4(&board, 6, 4);
+    let directions: Vec<&str> = lines.iter().map(|line| evaluate::direction_name(line.direction)).collect();
+    assert_eq!(directions, vec!["horizontal", "right diagonal"]);
+    assert_eq!(lines[1].spaces, vec![(6, 4), (5, 5), (4, 6), (3, 7)]);
+}
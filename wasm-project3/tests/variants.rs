@@ -0,0 +1,178 @@
+//! Tests for the variants registry and the rules of Five in a Row and Power-Up, run natively.
+
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use wasm_project3::bot::{self, BotConfig, Position, Status};
+use wasm_project3::evaluate;
+use wasm_project3::js::{MovePU, Variant};
+use wasm_project3::rules::{FiveInARow, GameMode, GameState, OutcomeC4, Power, PowerUp, Rules};
+use wasm_project3::{variants, Board};
+
+// Power-Up position after the given (col, power) moves on a 6x7 board, player 1 moving first
+fn power_up(moves: &[(usize, Power)]) -> GameState<PowerUp> {
+    power_up_on(Board::with_size(6, 7, 4), moves)
+}
+
+fn power_up_on(board: Board, moves: &[(usize, Power)]) -> GameState<PowerUp> {
+    let mut pos = GameState::new(PowerUp::default(), board);
+    for &mv in moves {
+        assert_eq!(pos.play(mv), Status::Ongoing);
+    }
+    pos
+}
+
+// the ids in the given column from the bottom up, PowerUp::WALL for a wall
+fn column(board: &Board, col: usize) -> Vec<usize> {
+    let top = board.drop_row(col).map_or(1, |row| row + 1);
+    (top..=board.num_rows()).rev().filter_map(|row| board.player_at(row, col)).collect()
+}
+
+#[test]
+fn registry_lists_every_mode_by_unique_id() {
+    for mode in variants::VARIANTS {
+        assert_eq!(variants::find(mode.id()), Some(mode));
+    }
+    assert_eq!(variants::find("five-in-a-row"), Some(GameMode::FiveInARow));
+    assert_eq!(variants::find("connect6"), None);
+
+    let variant = Variant::new(GameMode::FiveInARow);
+    assert_eq!((variant.num_rows, variant.num_cols, variant.win_len), (7, 9, 5));
+    assert_eq!(variant.name, "Five in a Row");
+    let json = serde_json::to_string(&Variant::new(GameMode::PowerUp)).unwrap();
+    assert!(json.starts_with(r#"{"id":"power-up","name":"Power-Up","description":"#), "{}", json);
+}
+
+#[test]
+fn powers_are_passed_to_js_by_name() {
+    for &power in Power::ALL.iter() {
+        assert_eq!(serde_json::to_string(&power).unwrap(), format!("\"{}\"", power.name()));
+        assert_eq!(Power::from_name(power.name()), Some(power));
+    }
+    let played = MovePU{row: 6, col: 3, power: Power::Anvil};
+    assert_eq!(serde_json::to_string(&played).unwrap(), r#"{"row":6,"col":3,"power":"anvil"}"#);
+}
+
+#[test]
+fn five_in_a_row_needs_five() {
+    let (num_rows, num_cols, win_len) = FiveInARow.board_size();
+    let mut pos = GameState::new(FiveInARow, Board::with_size(num_rows, num_cols, win_len));
+    // player 1 builds along the bottom row, player 2 stacks on top
+    for col in 1..=4 {
+        assert_eq!(pos.play((col, 1)), Status::Ongoing);
+        assert_eq!(pos.play((col, 2)), Status::Ongoing);
+    }
+    assert_eq!(pos.play((5, 1)), Status::Won);
+    assert_eq!(FiveInARow.outcome(&pos.board, 7, 5, 1), Some(OutcomeC4::Win(1)));
+}
+
+#[test]
+fn anvil_smashes_its_column_and_undo_restores_it() {
+    let mut pos = power_up(&[(3, Power::Disc), (3, Power::Disc), (3, Power::Wall)]);
+    assert_eq!(column(&pos.board, 3), vec![1, 2, PowerUp::WALL]);
+    assert!(pos.legal_moves().contains(&(3, Power::Anvil)));
+
+    assert_eq!(pos.play((3, Power::Anvil)), Status::Ongoing);
+    assert_eq!(column(&pos.board, 3), vec![2]);
+    assert_eq!(pos.rules.piece_at(&pos.board, 6, 3), Some(Power::Anvil));
+    assert_eq!(pos.rules.powers_on_board(), &[(6, 3, Power::Anvil)]);
+    assert_eq!(pos.rules.powers_left(2), vec![Power::Bomb, Power::Wall]);
+    // the wall player 1 played was smashed, but it is still used up
+    assert_eq!(pos.rules.powers_left(1), vec![Power::Anvil, Power::Bomb]);
+    assert!(!pos.legal_moves().iter().any(|&(_, power)| power == Power::Wall));
+
+    pos.undo((3, Power::Anvil));
+    assert_eq!(column(&pos.board, 3), vec![1, 2, PowerUp::WALL]);
+    assert_eq!(pos.rules.piece_at(&pos.board, 4, 3), Some(Power::Wall));
+    assert_eq!(pos.rules.powers_left(1), vec![Power::Anvil, Power::Bomb]);
+    assert_eq!(pos.rules.powers_left(2), vec![Power::Anvil, Power::Bomb, Power::Wall]);
+}
+
+#[test]
+fn bomb_takes_the_place_of_the_top_piece() {
+    let mut pos = power_up(&[(5, Power::Disc), (5, Power::Disc)]);
+    assert_eq!(pos.play((5, Power::Bomb)), Status::Ongoing);
+    assert_eq!(column(&pos.board, 5), vec![1, 1]);
+    assert_eq!(pos.rules.piece_at(&pos.board, 5, 5), Some(Power::Bomb));
+    // a bomb needs a piece to blow up and each player has one
+    assert!(!pos.legal_moves().iter().any(|&(col, power)| col == 1 && power == Power::Bomb));
+    pos.undo((5, Power::Bomb));
+    assert_eq!(column(&pos.board, 5), vec![1, 2]);
+    assert_eq!(pos.rules.piece_at(&pos.board, 5, 5), Some(Power::Disc));
+
+    // a bomb blown up by the other player's bomb is used up all the same
+    let mut pos = power_up(&[(5, Power::Disc), (5, Power::Disc), (5, Power::Bomb)]);
+    assert_eq!(pos.play((5, Power::Bomb)), Status::Ongoing);
+    assert_eq!(pos.board.player_at(5, 5), Some(2));
+    assert_eq!(pos.rules.powers_left(1), vec![Power::Anvil, Power::Wall]);
+    assert_eq!(pos.rules.powers_left(2), vec![Power::Anvil, Power::Wall]);
+    pos.undo((5, Power::Bomb));
+    assert_eq!(pos.rules.piece_at(&pos.board, 5, 5), Some(Power::Bomb));
+}
+
+#[test]
+fn bomb_undo_restores_a_tall_column() {
+    // 17 pieces in the middle column of an 18x3 board, the bottom one a wall
+    let mut moves = vec![(2, Power::Wall)];
+    moves.extend((0..16).map(|_| (2, Power::Disc)));
+    let mut pos = power_up_on(Board::with_size(18, 3, 4), &moves);
+    let before = pos.board.clone();
+    assert_eq!(column(&before, 2).len(), 17);
+
+    assert_eq!(pos.play((2, Power::Bomb)), Status::Ongoing);
+    pos.undo((2, Power::Bomb));
+    assert_eq!(column(&pos.board, 2), column(&before, 2));
+    assert_eq!(pos.rules.piece_at(&pos.board, 18, 2), Some(Power::Wall));
+    // the anvil has no limit on how many pieces it smashes either
+    assert_eq!(pos.play((2, Power::Anvil)), Status::Ongoing);
+    assert_eq!(column(&pos.board, 2), vec![2]);
+    pos.undo((2, Power::Anvil));
+    assert_eq!(column(&pos.board, 2), column(&before, 2));
+}
+
+#[test]
+fn wall_blocks_lines_and_wins_for_nobody() {
+    // player 2 has 3 along the bottom row and closes the fourth space with their own wall
+    let moves = [(7, Power::Disc), (2, Power::Disc), (7, Power::Disc), (3, Power::Disc), (6, Power::Disc), (4, Power::Disc), (6, Power::Disc)];
+    let pos = power_up(&moves);
+    let mut walled = pos.clone();
+    assert_eq!(walled.play((5, Power::Wall)), Status::Ongoing);
+    assert_eq!(walled.board.player_at(6, 5), Some(PowerUp::WALL));
+    assert_eq!(walled.rules.powers_on_board(), &[(6, 5, Power::Wall)]);
+    // the line can only be finished on the other side now, so it is worth less
+    assert!(evaluate::evaluate_board_C4(&walled.board, 2) < evaluate::evaluate_board_C4(&pos.board, 2));
+    // player 2 can still finish it in column 1
+    walled.to_move = 2;
+    assert_eq!(walled.play((1, Power::Disc)), Status::Won);
+}
+
+#[test]
+fn bots_play_and_take_back_whole_power_up_games() {
+    let mut rng = ChaCha8Rng::seed_from_u64(11);
+    let config = BotConfig{node_budget: 0, depth: 2, ..BotConfig::medium()};
+    let mut pos = power_up(&[]);
+    let mut played = vec![];
+    loop {
+        let mv = if rng.gen_bool(0.5) {
+            bot::choose_move(&mut pos, &config, &mut rng)
+        } else {
+            let moves = pos.legal_moves();
+            moves.get(rng.gen_range(0..moves.len().max(1))).copied()
+        };
+        let mv = match mv {
+            Some(mv) => mv,
+            None => break,
+        };
+        assert!(pos.legal_moves().contains(&mv));
+        played.push(mv);
+        if pos.play(mv) != Status::Ongoing {
+            break;
+        }
+    }
+    assert!(played.iter().any(|&(_, power)| power != Power::Disc), "no power piece was played");
+    for &mv in played.iter().rev() {
+        pos.undo(mv);
+    }
+    assert!((1..=7).all(|col| pos.board.drop_row(col) == Some(6)));
+    assert_eq!(pos.to_move, 1);
+    assert_eq!(pos.rules, PowerUp::default());
+}
@@ -4,6 +4,14 @@
 
 extern crate wasm_bindgen_test;
 use wasm_bindgen_test::*;
+use wasm_project3::bot::BotConfig;
+use wasm_project3::js::{Cell, Placed};
+use wasm_project3::puzzle::{PuzzleSession, MAX_WIN_IN};
+use wasm_project3::rules::GameMode;
+use wasm_project3::{
+    bot_move_C4, can_redo, insert_piece_C4, is_game_over, moves_played, new_board, new_board_cylinder,
+    new_board_sized, player_to_move, redo, set_game, set_toot_player, undo, word_of,
+};
 
 wasm_bindgen_test_configure!(run_in_browser);
 
@@ -11,3 +19,67 @@ wasm_bindgen_test_configure!(run_in_browser);
 fn pass() {
     assert_eq!(1 + 1, 2);
 }
+
+#[wasm_bindgen_test]
+fn toot_player_must_be_1_or_2() {
+    assert!(set_toot_player(3).is_err());
+    assert!(set_toot_player(0).is_err());
+    assert!(set_toot_player(2).is_ok());
+    assert_eq!(word_of(2), "TOOT");
+}
+
+#[wasm_bindgen_test]
+fn puzzles_are_won_in_1_to_max_win_in_moves() {
+    // player 1 wins in one move in column 4
+    let position = "connect4 6x7 4 -------/-------/-------/-------/-------/111-222 1";
+    assert!(PuzzleSession::new(position, 0).is_err());
+    assert!(PuzzleSession::new(position, MAX_WIN_IN + 1).is_err());
+    assert!(PuzzleSession::new(position, 1).is_ok());
+}
+
+#[wasm_bindgen_test]
+fn impossible_board_sizes_throw() {
+    set_game(GameMode::Connect4);
+    assert!(new_board_sized(0, 7, 4).is_err());
+    assert!(new_board_sized(6, 0, 4).is_err());
+    assert!(new_board_cylinder(6, 7, 1).is_err());
+    assert!(new_board_sized(6, 7, 4).is_ok());
+}
+
+#[wasm_bindgen_test]
+fn undo_takes_back_human_move_and_bot_reply() {
+    set_game(GameMode::Connect4);
+    new_board();
+    assert_eq!(insert_piece_C4(4, 1), 6);
+    let reply: Placed = serde_wasm_bindgen::from_value(bot_move_C4(2, &BotConfig::easy())).unwrap();
+    assert!(reply.row > 0);
+    assert_eq!(moves_played(), 2);
+
+    let cleared: Vec<Cell> = serde_wasm_bindgen::from_value(undo()).unwrap();
+    assert_eq!(cleared, vec![Cell{row: reply.row, col: reply.col}, Cell{row: 6, col: 4}]);
+    assert_eq!(moves_played(), 0);
+    assert_eq!(player_to_move(), 1);
+    assert!(can_redo());
+
+    let filled: Vec<Placed> = serde_wasm_bindgen::from_value(redo()).unwrap();
+    assert_eq!(filled.len(), 2);
+    assert_eq!(filled[1], reply);
+    assert_eq!(player_to_move(), 1);
+}
+
+#[wasm_bindgen_test]
+fn undo_clears_game_over() {
+    set_game(GameMode::Connect4);
+    new_board();
+    // player 1 wins along the bottom row
+    for &col in &[1, 1, 2, 2, 3, 3] {
+        insert_piece_C4(col, if moves_played() % 2 == 0 { 1 } else { 2 });
+    }
+    insert_piece_C4(4, 1);
+    assert!(is_game_over());
+    undo();
+    assert!(!is_game_over());
+    assert_eq!(player_to_move(), 1);
+    redo();
+    assert!(is_game_over());
+}
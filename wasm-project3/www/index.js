@@ -19,14 +19,14 @@ document.getElementById("toot-otto_nav").addEventListener("click", event => {
 document.getElementById("leaderboards_nav").addEventListener("click", goToLeaderboards);
 
 if (window.location.href == URL + "connect4.html") {
-    var game_mode = "Connect4";
+    var variant = "connect4";
     document.getElementById("restart").addEventListener("click", event => {
         window.location.reload();
     });
     var num_cols = 7;
     connect4();
 } else if (window.location.href == URL + "toototto.html") {
-    var game_mode = "Toot-Otto";
+    var variant = "toot-otto";
     document.getElementById("restart").addEventListener("click", event => {
         window.location.reload();
     });
@@ -144,6 +144,7 @@ function connect4() {
     document.getElementById("to_home").addEventListener("click", goToHome);
     var alt_colors = false;
     var opponent;
+    var bot_config;
     signed_in().then(function (signedIn) {
         if (signedIn) {
             var player_name = localStorage.getItem("signedInAs");
@@ -161,22 +162,32 @@ function connect4() {
             });
             document.getElementById("easybot").addEventListener("click", event => {
                 opponent = 1;
+                bot_config = wasm.BotConfig.easy();
                 console.log(`Opponent selected as: Easy Bot`);
                 document.getElementById("opponent_name").innerHTML = "    Easy Bot";
                 document.getElementById("menu").style.visibility = 'hidden';
             });
             document.getElementById("medbot").addEventListener("click", event => {
                 opponent = 2;
+                bot_config = wasm.BotConfig.medium();
                 console.log(`Opponent selected as: Medium Bot`);
                 document.getElementById("opponent_name").innerHTML = "    Medium Bot";
                 document.getElementById("menu").style.visibility = 'hidden';
             });
             document.getElementById("hardbot").addEventListener("click", event => {
                 opponent = 3;
+                bot_config = wasm.BotConfig.hard();
                 console.log(`Opponent selected as: Hard Bot`);
                 document.getElementById("opponent_name").innerHTML = "    Hard Bot";
                 document.getElementById("menu").style.visibility = 'hidden';
             });
+            document.getElementById("expertbot").addEventListener("click", event => {
+                opponent = 4;
+                bot_config = wasm.BotConfig.expert();
+                console.log(`Opponent selected as: Expert Bot`);
+                document.getElementById("opponent_name").innerHTML = "    Expert Bot";
+                document.getElementById("menu").style.visibility = 'hidden';
+            });
     
             document.getElementById("alt_colors").addEventListener("click", event => {
                 alt_colors = true;
@@ -194,9 +205,11 @@ function connect4() {
                 }
             })
     
-            // set game mode, then create board
-            wasm.set_game(game_mode);
+            // set the variant, then create board
+            wasm.set_variant(variant);
             wasm.new_board();
+            // the seed replays the bots' moves, include it when reporting a strange move
+            console.log(`Game seed: ${wasm.get_seed()}`);
             
             var player = 1; // next player = 2
             document.getElementById("player1box").style.background = "greenyellow";
@@ -224,6 +237,90 @@ function connect4() {
                 insert_piece(7, player, alt_colors, opponent);
             });
     
+            // take back the last move, against a bot its reply is taken back too
+            document.getElementById("undo").addEventListener("click", event => {
+                clear_highlight();
+                let cleared = wasm.undo();
+                for (let i = 0; i < cleared.length; i++) {
+                    let row = cleared[i].row;
+                    let col = cleared[i].col;
+                    document.getElementById(`R${row}C${col}`).innerHTML = "";
+                }
+                restore_turn();
+            });
+            // play again the moves taken back by undo
+            document.getElementById("redo").addEventListener("click", event => {
+                let filled = wasm.redo();
+                for (let i = 0; i < filled.length; i++) {
+                    let row = filled[i].row;
+                    let col = filled[i].col;
+                    let player_id = filled[i].piece;
+                    if (alt_colors) {
+                        document.getElementById(`R${row}C${col}`).innerHTML = `<span class="piece${player_id}_alt"></span>`;
+                    } else {
+                        document.getElementById(`R${row}C${col}`).innerHTML = `<span class="piece${player_id}"></span>`;
+                    }
+                }
+                if (wasm.is_game_over()) {  // a redone winning move
+                    let last = filled[filled.length - 1];
+                    highlight_lines(wasm.winning_lines_C4(last.row, last.col));
+                }
+                restore_turn();
+            });
+
+            // copy the game in column notation (e.g. "4453323") to share it
+            document.getElementById("share_game").addEventListener("click", event => {
+                let notation = wasm.game_notation_C4();
+                navigator.clipboard.writeText(notation);
+                wasm.notify(`Copied the game: ${notation}`);
+            });
+            // replace the game with one pasted in column notation
+            document.getElementById("load_game").addEventListener("click", event => {
+                let notation = prompt("Paste a game in column notation, e.g. 4453323");
+                if (notation == null) {
+                    return;
+                }
+                try {
+                    var placed = wasm.load_notation_C4(notation);
+                } catch (error) {
+                    wasm.notify(`Could not load the game: ${error}`);
+                    return;
+                }
+                clear_highlight();
+                let num_rows = wasm.board_size().num_rows;
+                for (let row = 1; row <= num_rows; row++) {
+                    for (let col = 1; col <= num_cols; col++) {
+                        document.getElementById(`R${row}C${col}`).innerHTML = "";
+                    }
+                }
+                for (let i = 0; i < placed.length; i++) {
+                    let row = placed[i].row;
+                    let col = placed[i].col;
+                    let player_id = placed[i].piece;
+                    if (alt_colors) {
+                        document.getElementById(`R${row}C${col}`).innerHTML = `<span class="piece${player_id}_alt"></span>`;
+                    } else {
+                        document.getElementById(`R${row}C${col}`).innerHTML = `<span class="piece${player_id}"></span>`;
+                    }
+                }
+                if (wasm.is_game_over() && placed.length > 0) {
+                    let last = placed[placed.length - 1];
+                    highlight_lines(wasm.winning_lines_C4(last.row, last.col));
+                }
+                restore_turn();
+            });
+
+            // after an undo or redo, enable the columns that have space and highlight the player to move
+            function restore_turn() {
+                player = wasm.player_to_move();
+                document.getElementById("player1box").style.background = (player == 1) ? "greenyellow" : "";
+                document.getElementById("player2box").style.background = (player == 2) ? "greenyellow" : "";
+                for (var i = 1; i <= num_cols; i++) {
+                    var full = document.getElementById(`R1C${i}`).innerHTML != "";
+                    document.getElementById(`Col${i}`).disabled = full || wasm.is_game_over();
+                }
+            }
+
             // insert a piece onto the board by "dropping" it in a column
             function insert_piece(col, player_id, alt_colors, opponent) {
                 // returns the row that the piece was inserted in
@@ -243,6 +340,7 @@ function connect4() {
                     // check for win
                     var win = wasm.check_for_win_C4(row, col, player_id);
                     if (win) {
+                        highlight_lines(wasm.winning_lines_C4(row, col));
                         winner(player_id);
                     } else {
                         switch_player(opponent);
@@ -265,7 +363,12 @@ function connect4() {
                         document.getElementById(`Col${col}`).disabled = true;
                     }
                     var win = wasm.check_for_win_C4(row, col, player_id);
-                    switch_player(opponent);
+                    if (win) {
+                        highlight_lines(wasm.winning_lines_C4(row, col));
+                        winner(player_id);
+                    } else {
+                        switch_player(opponent);
+                    }
                 } else {
                     wasm.notify(`Column ${col} is full.`);
                 }
@@ -277,24 +380,11 @@ function connect4() {
                     player = 2;
                     document.getElementById("player1box").style.background = "";
                     document.getElementById("player2box").style.background = "greenyellow";
-                    if (opponent == 1) { // easy bot
-                        let data = [];
-                        data = wasm.easy_bot_C4(player);
-                        let row = data[0];
-                        let column = data[1];
-                        insert_piece_bot(row ,column, player, opponent);
-                    } else if (opponent == 2) {  // medium bot
-                        let data = [];
-                        data = wasm.medium_C4(player);
-                        let row = data[0];
-                        let column = data[1];
+                    if (opponent > 0) { // bot with the selected difficulty
+                        let data = wasm.bot_move_C4(player, bot_config);
+                        let row = data.row;
+                        let column = data.col;
                         insert_piece_bot(row, column, player, opponent);
-                    } else if (opponent == 3) {  // hard bot
-                        let data = [];
-                        data = wasm.difficult_C4(player);
-                        let row = data[0];
-                        let column = data[1];
-                        insert_piece_bot(row, column, player, opponent, alt_colors);
                     }
                 } else if (player == 2) {
                     player = 1;
@@ -314,6 +404,7 @@ function toot_otto() {
     signed_in().then(function (signedIn) {
         if (signedIn) {
             var opponent;
+            var bot_config;
             var player_name = localStorage.getItem("signedInAs");
             document.getElementById("notSignedIn").style.display = 'none';
             document.getElementById("signedIn").style.display = 'block';
@@ -336,6 +427,7 @@ function toot_otto() {
             document.getElementById("easybot").addEventListener("click", event => {
                 opponent = 1;
                 opponent_name = "Easy Bot";
+                bot_config = wasm.BotConfig.easy();
                 console.log(`Opponent selected as: ${opponent_name}`);
                 document.getElementById("menu").style.visibility = 'hidden';
                 document.getElementById("player2box").innerHTML = `<h3 class="center" style="margin-left: 10px">${opponent_name}: ${player2_phrase}</h3>`;
@@ -343,6 +435,7 @@ function toot_otto() {
             document.getElementById("medbot").addEventListener("click", event => {
                 opponent = 2;
                 opponent_name = "Medium Bot";
+                bot_config = wasm.BotConfig.medium();
                 console.log(`Opponent selected as: ${opponent_name}`);
                 document.getElementById("menu").style.visibility = 'hidden';
                 document.getElementById("player2box").innerHTML = `<h3 class="center" style="margin-left: 10px">${opponent_name}: ${player2_phrase}</h3>`;
@@ -350,14 +443,26 @@ function toot_otto() {
             document.getElementById("hardbot").addEventListener("click", event => {
                 opponent = 3;
                 opponent_name = "Hard Bot";
+                bot_config = wasm.BotConfig.hard();
+                console.log(`Opponent selected as: ${opponent_name}`);
+                document.getElementById("menu").style.visibility = 'hidden';
+                document.getElementById("player2box").innerHTML = `<h3 class="center" style="margin-left: 10px">${opponent_name}: ${player2_phrase}</h3>`;
+            });
+            document.getElementById("expertbot").addEventListener("click", event => {
+                opponent = 4;
+                opponent_name = "Expert Bot";
+                bot_config = wasm.BotConfig.expert();
                 console.log(`Opponent selected as: ${opponent_name}`);
                 document.getElementById("menu").style.visibility = 'hidden';
                 document.getElementById("player2box").innerHTML = `<h3 class="center" style="margin-left: 10px">${opponent_name}: ${player2_phrase}</h3>`;
             });
 
-            // set game mode, then create board
-            wasm.set_game(game_mode);
+            // set the variant, then create board
+            wasm.set_variant(variant);
             wasm.new_board();
+            wasm.set_toot_player(1);
+            // the seed replays the bots' moves, include it when reporting a strange move
+            console.log(`Game seed: ${wasm.get_seed()}`);
 
             // set phrases in player boxes
             document.getElementById("player1box").innerHTML = `<h3 class="center" style="margin-left: 10px">${player_name}: ${player1_phrase}</h3>`;
@@ -367,15 +472,17 @@ function toot_otto() {
             document.getElementById("player1box").style.background = "greenyellow";
 
             // set first letter and letter selection button colors
+            show_letters_left();
             var letter = 'T';
             document.getElementById("T_button").style.background = "greenyellow";
             document.getElementById("O_button").style.background = "white";
 
             // allow the player to switch phrases to play as TOOT or OTTO
             document.getElementById("switch_phrases").addEventListener("click", event => {
-                var temp = player1_phrase;
-                player1_phrase = player2_phrase;
-                player2_phrase = temp;
+                // the game decides wins by who owns the spelled word
+                wasm.set_toot_player(player1_phrase == "TOOT" ? 2 : 1);
+                player1_phrase = wasm.word_of(1);
+                player2_phrase = wasm.word_of(2);
                 document.getElementById("player1box").innerHTML = `<h3 class="center" style="margin-left: 10px">${player_name}: ${player1_phrase}</h3>`;
                 document.getElementById("player2box").innerHTML = `<h3 class="center" style="margin-left: 10px">${opponent_name}: ${player2_phrase}</h3>`;
             });
@@ -408,14 +515,107 @@ function toot_otto() {
                 insert_piece(6, letter, opponent);
             });
 
+            // take back the last move, against a bot its reply is taken back too
+            document.getElementById("undo").addEventListener("click", event => {
+                clear_highlight();
+                let cleared = wasm.undo();
+                for (let i = 0; i < cleared.length; i++) {
+                    let row = cleared[i].row;
+                    let col = cleared[i].col;
+                    document.getElementById(`R${row}C${col}`).innerHTML = "";
+                }
+                restore_turn();
+            });
+            // play again the moves taken back by undo
+            document.getElementById("redo").addEventListener("click", event => {
+                let filled = wasm.redo();
+                for (let i = 0; i < filled.length; i++) {
+                    let row = filled[i].row;
+                    let col = filled[i].col;
+                    let letter = filled[i].piece;
+                    document.getElementById(`R${row}C${col}`).innerHTML = `<span class="piece${letter}">${letter}</span>`;
+                }
+                restore_turn();
+            });
+
+            // copy the game in notation (e.g. "3T 4O 3O" after the player and word headers) to share it
+            document.getElementById("share_game").addEventListener("click", event => {
+                let notation = wasm.game_notation_TO(player_name, opponent_name);
+                navigator.clipboard.writeText(notation);
+                wasm.notify(`Copied the game:\n${notation}`);
+            });
+            // replace the game with a pasted one, the players take the words it was played with
+            document.getElementById("load_game").addEventListener("click", event => {
+                let notation = prompt("Paste a game in notation, e.g. 3T 4O 3O");
+                if (notation == null) {
+                    return;
+                }
+                try {
+                    var placed = wasm.load_notation_TO(notation);
+                } catch (error) {
+                    wasm.notify(`Could not load the game: ${error}`);
+                    return;
+                }
+                clear_highlight();
+                let num_rows = wasm.board_size().num_rows;
+                for (let row = 1; row <= num_rows; row++) {
+                    for (let col = 1; col <= num_cols; col++) {
+                        document.getElementById(`R${row}C${col}`).innerHTML = "";
+                    }
+                }
+                for (let i = 0; i < placed.length; i++) {
+                    let row = placed[i].row;
+                    let col = placed[i].col;
+                    let letter = placed[i].piece;
+                    document.getElementById(`R${row}C${col}`).innerHTML = `<span class="piece${letter}">${letter}</span>`;
+                }
+                player1_phrase = wasm.word_of(1);
+                player2_phrase = wasm.word_of(2);
+                document.getElementById("player1box").innerHTML = `<h3 class="center" style="margin-left: 10px">${player_name}: ${player1_phrase}</h3>`;
+                document.getElementById("player2box").innerHTML = `<h3 class="center" style="margin-left: 10px">${opponent_name}: ${player2_phrase}</h3>`;
+                restore_turn();
+            });
+
+            // after an undo or redo, enable the columns that have space and highlight the player to move
+            function restore_turn() {
+                player = wasm.player_to_move();
+                document.getElementById("player1box").style.background = (player == 1) ? "greenyellow" : "";
+                document.getElementById("player2box").style.background = (player == 2) ? "greenyellow" : "";
+                for (var i = 1; i <= num_cols; i++) {
+                    var full = document.getElementById(`R1C${i}`).innerHTML != "";
+                    document.getElementById(`Col${i}`).disabled = full || wasm.is_game_over();
+                }
+                // phrases can be switched again once the board is empty
+                document.getElementById("switch_phrases").disabled = wasm.moves_played() > 0;
+                show_letters_left();
+                if (wasm.is_game_over()) {  // a redone winning move
+                    highlight_lines(wasm.words_on_board_TO());
+                }
+            }
+
+            // show how many of each letter the player to move has left
+            function show_letters_left() {
+                var next_player = wasm.player_to_move();
+                var left = wasm.letters_left(next_player);
+                document.getElementById("letters_left").innerHTML = `Letters left: ${left.t} T, ${left.o} O`;
+            }
+
             // insert a piece onto the board by "dropping" it in a column
             function insert_piece(col, letter, opponent) {
                 // disable switching phrases after first piece is played
                 document.getElementById("switch_phrases").disabled = true;
 
+                // each player only has so many of each letter
+                var left = wasm.letters_left(player);
+                if ((letter == 'T' ? left.t : left.o) == 0) {
+                    wasm.notify(`You have no ${letter}'s left.`);
+                    return;
+                }
+
                 // returns the row that the piece was inserted in
-                var row = wasm.insert_piece_TO(col, letter); 
+                var row = wasm.insert_piece_TO(col, letter, player); 
                 if (row > 0) {  // insert was successful
+                    show_letters_left();
                     // set piece in the space
                     var id = "R" + row.toString() + "C" + col.toString();
                     document.getElementById(id).innerHTML = `<span class="piece${letter}">${letter}</span>`;
@@ -425,12 +625,9 @@ function toot_otto() {
                     console.log(`Player ${player} inserted a '${letter}' piece at ${id}.`);
                     // check for win
                     var win = wasm.check_for_win_TO(row, col);
-                    if (win == 1) { // TOOT player has won
-                        if (player1_phrase == "TOOT") {winner(1);} else {winner(2);}
-                    } else if (win == 2) {  // OTTO player has won
-                        if (player1_phrase == "OTTO") {winner(1);} else {winner(2);}
-                    } else if (win == 3) {  // tie
-                        winner(3);
+                    if (win > 0) { // the owner of the spelled word has won, or 3 for a tie
+                        highlight_lines(wasm.winning_words_TO(row, col));
+                        winner(win);
                     } else {
                         switch_player(opponent);
                     }
@@ -442,18 +639,16 @@ function toot_otto() {
             function insert_piece_bot(row, col, letter, player_id, opponent) {
                 console.log(`Bot inserted piece at R${row}C${col}.`);
                 if (row > 0) {
+                    show_letters_left();
                     var id = "R" + row.toString() + "C" + col.toString();
                     document.getElementById(id).innerHTML = `<span class="piece${letter}">${letter}</span>`;
                     if (row == 1) {
                         document.getElementById(`Col${col}`).disabled = true;
                     }
-                    var win = wasm.check_for_win_TO(row, col, player_id);
-                    if (win == 1) { // TOOT player has won
-                        if (player1_phrase == "TOOT") {winner(1);} else {winner(2);}
-                    } else if (win == 2) {  // OTTO player has won
-                        if (player1_phrase == "OTTO") {winner(1);} else {winner(2);}
-                    } else if (win == 3) {  // tie
-                        winner(3);
+                    var win = wasm.check_for_win_TO(row, col);
+                    if (win > 0) { // the owner of the spelled word has won, or 3 for a tie
+                        highlight_lines(wasm.winning_words_TO(row, col));
+                        winner(win);
                     } else {
                         switch_player(opponent);
                     }
@@ -468,26 +663,11 @@ function toot_otto() {
                     player = 2;
                     document.getElementById("player1box").style.background = "";
                     document.getElementById("player2box").style.background = "greenyellow";
-                    if (opponent == 1) { // easy bot
-                        let data = [];
-                        data = wasm.easy_otto();
-                        let row = data[0];
-                        let column = data[1];
-                        let letter = data[2];
-                        insert_piece_bot(row, column, letter, player, opponent);
-                    } else if (opponent == 2) {  // medium bot
-                        let data = [];
-                        data = wasm.medium_TO(player);
-                        let row = data[0];
-                        let column = data[1];
-                        let letter = data[2];
-                        insert_piece_bot(row, column, letter, player, opponent);
-                    } else if (opponent == 3) {  // hard bot
-                        let data = [];
-                        data = wasm.difficult_TO(player);
-                        let row = data[0];
-                        let column = data[1];
-                        let letter = data[2];
+                    if (opponent > 0) { // bot with the selected difficulty
+                        let data = wasm.bot_move_TO(player, bot_config);
+                        let row = data.row;
+                        let column = data.col;
+                        let letter = data.piece;
                         insert_piece_bot(row, column, letter, player, opponent);
                     }
                 } else if (player == 2) {
@@ -511,6 +691,8 @@ function toot_otto() {
 
 // display the winning message, change player box colors, and disable all column buttons
 function winner(player_id) {
+    // the position can be loaded with from_position_string, include it when reporting a bug
+    console.log(`Final position: ${wasm.to_position_string()}`);
     if (player_id == 0) {
         wasm.notify(`No winner...`);
         document.getElementById(`player1box`).style.background = "white";
@@ -574,8 +756,20 @@ function winner(player_id) {
 
 }
 
+// highlight the spaces of winning lines or words
+function highlight_lines(lines) {
+    for (let i = 0; i < lines.length; i++) {
+        let spaces = lines[i].spaces;
+        for (let j = 0; j < spaces.length; j++) {
+            document.getElementById(`R${spaces[j].row}C${spaces[j].col}`).classList.add("winning_space");
+        }
+    }
+}
 
-
-
-
-
+// remove the highlight of the winning lines, e.g. when the winning move is undone
+function clear_highlight() {
+    var spaces = document.getElementsByClassName("winning_space");
+    while (spaces.length) {
+        spaces[0].classList.remove("winning_space");
+    }
+}